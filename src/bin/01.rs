@@ -1,3 +1,6 @@
+use advent_of_code::matcher::Matcher;
+use lazy_static::lazy_static;
+
 advent_of_code::solution!(1);
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -14,7 +17,18 @@ pub fn part_one(input: &str) -> Option<u32> {
     )
 }
 
-const NAMED_DIGITS: [(&str, u32); 10] = [
+/// Digits and their spelled-out names.
+const DIGITS: [(&str, u32); 20] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
     ("zero", 0),
     ("one", 1),
     ("two", 2),
@@ -27,23 +41,15 @@ const NAMED_DIGITS: [(&str, u32); 10] = [
     ("nine", 9),
 ];
 
-fn get_digits(line: &str) -> Vec<u32> {
-    let mut digits = Vec::new();
-    let chars = line.chars().collect::<Vec<_>>();
-    for i in 0..line.len() {
-        if let Some(digit) = chars[i].to_digit(10) {
-            digits.push(digit);
-            continue;
-        }
-        let remain = &line[i..];
-        for (name, digit) in NAMED_DIGITS {
-            if remain.starts_with(name) {
-                digits.push(digit);
-                break;
-            }
-        }
-    }
-    digits
+lazy_static! {
+    static ref DIGIT_MATCHER: Matcher = Matcher::new(DIGITS.iter().map(|(pattern, _)| pattern));
+}
+
+/// Returns the first and last digit of a line. Names may overlap, e.g. `oneight` ends with an eight.
+fn first_and_last_digit(line: &str) -> Option<(u32, u32)> {
+    let first = DIGIT_MATCHER.first(line)?;
+    let last = DIGIT_MATCHER.last(line)?;
+    Some((DIGITS[first.pattern].1, DIGITS[last.pattern].1))
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -51,9 +57,7 @@ pub fn part_two(input: &str) -> Option<u32> {
         input
             .lines()
             .map(|line| {
-                let digits = get_digits(line);
-                let first = digits.first().unwrap();
-                let last = digits.last().unwrap_or(first);
+                let (first, last) = first_and_last_digit(line).unwrap();
                 first * 10 + last
             })
             .sum(),
@@ -79,4 +83,12 @@ mod tests {
         ));
         assert_eq!(result, Some(281));
     }
+
+    #[test]
+    fn test_first_and_last_digit() {
+        assert_eq!(first_and_last_digit("oneight"), Some((1, 8)));
+        assert_eq!(first_and_last_digit("7"), Some((7, 7)));
+        assert_eq!(first_and_last_digit("ñtwoé3ü"), Some((2, 3)));
+        assert_eq!(first_and_last_digit("ééseveñ"), None);
+    }
 }
@@ -1,122 +1,323 @@
+use std::{collections::BTreeMap, fs, sync::OnceLock};
+
+use advent_of_code::template::options::ExtraArgs;
+use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
-    character::complete::alpha1,
+    character::complete::{self, alpha1, space1},
+    combinator::all_consuming,
     multi::separated_list1,
-    sequence::{preceded, terminated},
-    IResult,
+    sequence::{preceded, separated_pair},
+    IResult, Parser,
 };
 
-advent_of_code::solution!(2);
+advent_of_code::solution!(2, explain = explain, configure = configure);
+
+/// Number of cubes per colour.
+type Cubes = BTreeMap<String, u32>;
 
-#[derive(Clone, Copy)]
-enum Cube {
-    Blue(u32),
-    Red(u32),
-    Green(u32),
+fn cube(input: &str) -> IResult<&str, (u32, &str)> {
+    separated_pair(complete::u32, space1, alpha1)(input)
 }
 
-impl Cube {
-    fn over_max(self) -> bool {
-        match self {
-            Cube::Blue(x) => x > 14,
-            Cube::Red(x) => x > 12,
-            Cube::Green(x) => x > 13,
+/// Parses a list like `3 blue, 4 red`. Colours that are listed more than once are added up.
+fn cubes(input: &str) -> IResult<&str, Cubes> {
+    separated_list1(tag(", "), cube)
+        .map(|cubes| {
+            cubes
+                .into_iter()
+                .fold(Cubes::new(), |mut acc, (count, colour)| {
+                    *acc.entry(colour.to_string()).or_default() += count;
+                    acc
+                })
+        })
+        .parse(input)
+}
+
+fn format_cubes(cubes: &Cubes) -> String {
+    cubes
+        .iter()
+        .map(|(colour, count)| format!("{count} {colour}"))
+        .join(", ")
+}
+
+/// The cubes the elf has put into the bag.
+#[derive(Debug, PartialEq)]
+struct Bag(Cubes);
+
+impl Bag {
+    /// The bag from the puzzle description.
+    fn puzzle() -> Self {
+        Self(Cubes::from([
+            ("red".to_string(), 12),
+            ("green".to_string(), 13),
+            ("blue".to_string(), 14),
+        ]))
+    }
+
+    /// Parses a bag in the same format as a draw, e.g. `12 red, 13 green, 14 blue`.
+    fn parse(spec: &str) -> Result<Self, String> {
+        all_consuming(cubes)(spec.trim())
+            .map(|(_, cubes)| Self(cubes))
+            .map_err(|_| format!("invalid bag `{spec}`, expected e.g. `12 red, 13 green, 14 blue`"))
+    }
+
+    /// Reads the bag from `--bag <cubes>` or from the file passed via `--bag-file <path>`.
+    /// Falls back to the bag from the puzzle description.
+    fn from_args(args: &mut ExtraArgs) -> Result<Self, String> {
+        let spec: Option<String> = args.value("--bag")?;
+        let path: Option<String> = args.value("--bag-file")?;
+
+        match (spec, path) {
+            (Some(_), Some(_)) => Err("`--bag` and `--bag-file` cannot be combined".into()),
+            (Some(spec), None) => Bag::parse(&spec),
+            (None, Some(path)) => {
+                let spec = fs::read_to_string(&path)
+                    .map_err(|e| format!("could not read bag file `{path}`: {e}"))?;
+                Bag::parse(&spec)
+            }
+            (None, None) => Ok(Bag::puzzle()),
         }
     }
+
+    /// Colours that are not in the bag have a limit of zero.
+    fn limit(&self, colour: &str) -> u32 {
+        self.0.get(colour).copied().unwrap_or(0)
+    }
+
+    fn power(&self) -> u32 {
+        self.0.values().product()
+    }
 }
 
-fn cube(input: &str) -> IResult<&str, Cube> {
-    let (input, digit) = terminated(nom::character::complete::u32, tag(" "))(input)?;
-    let (input, cube) = alpha1(input)?;
-    let cube = match cube {
-        "blue" => Cube::Blue(digit),
-        "red" => Cube::Red(digit),
-        "green" => Cube::Green(digit),
-        _ => panic!("Invalid character"),
-    };
-    Ok((input, cube))
+/// A colour of a draw that exceeds the bag.
+#[derive(Debug, PartialEq)]
+struct Violation {
+    /// Index of the draw within the game.
+    draw: usize,
+    colour: String,
+    count: u32,
+    limit: u32,
 }
 
-fn cubes(input: &str) -> IResult<&str, Vec<Cube>> {
-    separated_list1(tag(", "), cube)(input)
+#[derive(Debug, PartialEq)]
+struct Game {
+    id: u32,
+    draws: Vec<Cubes>,
 }
 
-fn subsets(input: &str) -> IResult<&str, Vec<Vec<Cube>>> {
-    separated_list1(tag("; "), cubes)(input)
+impl Game {
+    fn parse(input: &str) -> IResult<&str, Game> {
+        let (input, id) = preceded(tag("Game "), complete::u32)(input)?;
+        let (input, draws) = preceded(tag(": "), separated_list1(tag("; "), cubes))(input)?;
+
+        Ok((input, Game { id, draws }))
+    }
+
+    /// Lists every colour of every draw that holds more cubes than the bag.
+    fn violations(&self, bag: &Bag) -> Vec<Violation> {
+        self.draws
+            .iter()
+            .enumerate()
+            .flat_map(|(draw, cubes)| {
+                cubes
+                    .iter()
+                    .filter(|(colour, &count)| count > bag.limit(colour))
+                    .map(move |(colour, &count)| Violation {
+                        draw,
+                        colour: colour.clone(),
+                        count,
+                        limit: bag.limit(colour),
+                    })
+            })
+            .collect()
+    }
+
+    fn is_possible(&self, bag: &Bag) -> bool {
+        self.violations(bag).is_empty()
+    }
+
+    /// The smallest bag that makes the game possible. Colours of `bag` that are never drawn are kept with a count
+    /// of zero, so they still take part in the power.
+    fn minimum_bag(&self, bag: &Bag) -> Bag {
+        let mut minimum: Cubes = bag.0.keys().map(|colour| (colour.clone(), 0)).collect();
+        for (colour, &count) in self.draws.iter().flatten() {
+            let entry = minimum.entry(colour.clone()).or_default();
+            *entry = (*entry).max(count);
+        }
+        Bag(minimum)
+    }
+}
+
+/// The bag the parts are solved for, set by [`configure`].
+static BAG: OnceLock<Bag> = OnceLock::new();
+
+fn configure(args: &mut ExtraArgs) -> Result<(), String> {
+    let bag = Bag::from_args(args)?;
+    BAG.set(bag)
+        .map_err(|_| "the bag is already set".to_string())
 }
 
-fn game(input: &str) -> IResult<&str, (u32, Vec<Vec<Cube>>)> {
-    let (input, id) = preceded(tag("Game "), nom::character::complete::u32)(input)?;
-    let (input, cube_subsets) = preceded(tag(": "), subsets)(input)?;
+/// The configured bag, or the one from the puzzle description if the parts run without [`configure`], e.g. in tests.
+fn bag() -> &'static Bag {
+    BAG.get_or_init(Bag::puzzle)
+}
 
-    Ok((input, (id, cube_subsets)))
+fn parse(input: &str) -> Vec<Game> {
+    input
+        .lines()
+        .map(|line| Game::parse(line).unwrap().1)
+        .collect()
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
+    part_one_with(input, bag())
+}
+
+fn part_one_with(input: &str, bag: &Bag) -> Option<u32> {
     Some(
-        input
-            .lines()
-            .map(|line| {
-                let (_, game) = game(line).unwrap();
-                game
-            })
-            .flat_map(|(id, game)| {
-                let invalid_game = game
-                    .iter()
-                    .any(|subsets| subsets.iter().any(|x| x.over_max()));
-                if invalid_game {
-                    None
-                } else {
-                    Some(id)
-                }
-            })
+        parse(input)
+            .iter()
+            .filter(|game| game.is_possible(bag))
+            .map(|game| game.id)
             .sum(),
     )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
+    part_two_with(input, bag())
+}
+
+fn part_two_with(input: &str, bag: &Bag) -> Option<u32> {
     Some(
-        input
-            .lines()
-            .map(|line| {
-                let (_, (_, cubes)) = game(line).unwrap();
-                let (mut blue, mut green, mut red) = (0, 0, 0);
-                cubes.iter().for_each(|subset| {
-                    for cube in subset {
-                        match *cube {
-                            Cube::Blue(x) => {
-                                if x > blue {
-                                    blue = x;
-                                }
-                            }
-                            Cube::Red(x) => {
-                                if x > red {
-                                    red = x;
-                                }
-                            }
-                            Cube::Green(x) => {
-                                if x > green {
-                                    green = x;
-                                }
-                            }
-                        }
-                    }
-                });
-
-                blue * green * red
-            })
+        parse(input)
+            .iter()
+            .map(|game| game.minimum_bag(bag).power())
             .sum(),
     )
 }
 
+/// Prints for every game whether it is possible with the bag given as `value` and which draws make it impossible.
+fn explain(input: &str, value: &str) {
+    let bag = match Bag::parse(value) {
+        Ok(bag) => bag,
+        Err(e) => {
+            eprintln!("{e}");
+            return;
+        }
+    };
+
+    println!("Bag: {}\n", format_cubes(&bag.0));
+
+    for game in parse(input) {
+        let minimum = format_cubes(&game.minimum_bag(&bag).0);
+        let violations = game.violations(&bag);
+        if violations.is_empty() {
+            println!("Game {}: possible (minimum bag: {minimum})", game.id);
+            continue;
+        }
+
+        println!("Game {}: impossible (minimum bag: {minimum})", game.id);
+        for v in violations {
+            println!(
+                "  draw {}: {} {} but the bag only holds {}",
+                v.draw + 1,
+                v.count,
+                v.colour,
+                v.limit
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_game() {
+        let (_, game) = Game::parse("Game 7: 1 purple, 2 red, 1 purple; 3 green").unwrap();
+        assert_eq!(game.id, 7);
+        assert_eq!(
+            game.draws,
+            vec![
+                Cubes::from([("purple".to_string(), 2), ("red".to_string(), 2)]),
+                Cubes::from([("green".to_string(), 3)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bag() {
+        assert_eq!(Bag::parse("12 red, 13 green, 14 blue"), Ok(Bag::puzzle()));
+        assert!(Bag::parse("12 red,").is_err());
+        assert_eq!(Bag::puzzle().limit("purple"), 0);
+    }
+
+    #[test]
+    fn test_bag_args() {
+        use advent_of_code::template::options::RunOptions;
+
+        let bag = |args: &[&str]| {
+            let options = RunOptions::parse(args.iter().map(ToString::to_string).collect())?;
+            Bag::from_args(&mut options.extra_args())
+        };
+        assert_eq!(bag(&[]), Ok(Bag::puzzle()));
+        assert_eq!(bag(&["--bag", "1 red"]), Bag::parse("1 red"));
+        assert!(bag(&["--bag"]).is_err());
+        assert!(bag(&["--bag-file", "missing.txt"]).is_err());
+        assert!(bag(&["--bag", "1 red", "--bag-file", "bag.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_violations() {
+        let (_, game) = Game::parse(
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red, 1 pink",
+        )
+        .unwrap();
+        let bag = Bag::puzzle();
+        assert_eq!(
+            game.violations(&bag),
+            vec![
+                Violation {
+                    draw: 0,
+                    colour: "red".to_string(),
+                    count: 20,
+                    limit: 12
+                },
+                Violation {
+                    draw: 2,
+                    colour: "pink".to_string(),
+                    count: 1,
+                    limit: 0
+                },
+            ]
+        );
+        assert!(game.is_possible(&Bag::parse("20 red, 13 green, 6 blue, 1 pink").unwrap()));
+    }
+
+    #[test]
+    fn test_minimum_bag() {
+        let (_, game) =
+            Game::parse("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+        assert_eq!(
+            game.minimum_bag(&Bag::puzzle()),
+            Bag::parse("4 red, 2 green, 6 blue").unwrap()
+        );
+        assert_eq!(game.minimum_bag(&Bag::puzzle()).power(), 48);
+
+        let (_, game) = Game::parse("Game 2: 3 blue, 4 red").unwrap();
+        assert_eq!(game.minimum_bag(&Bag::puzzle()).power(), 0);
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(8));
+
+        let bag = Bag::parse("20 red, 20 green, 20 blue").unwrap();
+        let result = part_one_with(&advent_of_code::template::read_file("examples", DAY), &bag);
+        assert_eq!(result, Some(15));
     }
 
     #[test]
@@ -1,6 +1,6 @@
-use std::collections::HashSet;
+use rand::{rngs::StdRng, seq::IndexedRandom, RngExt};
 
-advent_of_code::solution!(3);
+advent_of_code::solution!(3, generate = generate_input);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Coordinate {
@@ -8,25 +8,6 @@ struct Coordinate {
     y: usize,
 }
 
-impl Coordinate {
-    fn adjacents(self) -> Vec<Coordinate> {
-        (-1..=1)
-            .into_iter()
-            .flat_map(|x| (-1..=1).into_iter().flat_map(move |y| self.shift(x, y)))
-            .collect()
-    }
-
-    fn shift(self, x: i32, y: i32) -> Option<Coordinate> {
-        if (x, y) == (0, 0) {
-            return None;
-        }
-        let new_x = self.x.checked_add_signed(x as isize)?;
-        let new_y = self.y.checked_add_signed(y as isize)?;
-
-        Some((new_x, new_y).into())
-    }
-}
-
 impl From<(usize, usize)> for Coordinate {
     fn from(value: (usize, usize)) -> Self {
         Self {
@@ -36,25 +17,32 @@ impl From<(usize, usize)> for Coordinate {
     }
 }
 
-fn line(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
-    if start.y != end.y {
-        panic!("Not a horizonal line");
+impl From<Coordinate> for (usize, usize) {
+    fn from(value: Coordinate) -> Self {
+        (value.x, value.y)
     }
-    (start.x..=end.x)
-        .into_iter()
-        .map(move |x| (x, start.y).into())
-        .collect()
 }
 
+/// Coordinates around a horizontal line, each listed once.
 fn adjacent_coords(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
-    let line = line(start, end);
-    line.iter()
-        .flat_map(|c| c.adjacents())
-        .filter(|c| !line.contains(c))
-        .collect::<HashSet<_>>()
+    if start.y != end.y {
+        panic!("Not a horizonal line");
+    }
+    let left = start.x.checked_sub(1);
+    let right = end.x + 1;
+    let xs = left.unwrap_or(start.x)..=right;
+
+    let above = start
+        .y
+        .checked_sub(1)
         .into_iter()
-        .collect()
+        .flat_map(|y| xs.clone().map(move |x| (x, y).into()));
+    let below = xs.clone().map(|x| (x, start.y + 1).into());
+    let sides = left.into_iter().chain([right]).map(|x| (x, start.y).into());
+
+    above.chain(sides).chain(below).collect()
 }
+
 #[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 enum Element {
     PartNumber {
@@ -82,7 +70,9 @@ impl Element {
 
     fn is_at(&self, coordinate: Coordinate) -> bool {
         match self {
-            Element::PartNumber { start, end, .. } => line(*start, *end).contains(&coordinate),
+            Element::PartNumber { start, end, .. } => {
+                start.y == coordinate.y && (start.x..=end.x).contains(&coordinate.x)
+            }
             Element::Symbol { coordinate: c, .. } => *c == coordinate,
             Element::Empty { coordinate: c } => *c == coordinate,
         }
@@ -117,102 +107,100 @@ impl Element {
     }
 }
 
+/// The schematic as a dense grid of cells, each pointing to the element covering it.
 #[derive(Debug)]
 struct Map {
     elements: Vec<Element>,
+    width: usize,
+    /// Element id per cell, row by row. Cells past the end of shorter rows are `None`.
+    cells: Vec<Option<usize>>,
 }
 
 impl Map {
     fn new(input: &str) -> Self {
-        let elements = input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                let mut row = Vec::new();
-                let mut current_digit: Option<(usize, usize)> = None;
-                let chars: Vec<_> = line.chars().collect();
-
-                for i in 0..line.len() {
-                    let coordinate = Coordinate { x: i, y };
-                    let current_char = chars[i];
-                    if current_char.is_digit(10) {
-                        current_digit = match current_digit {
-                            Some((start, _)) => Some((start, i)),
-                            None => Some((i, i)),
-                        };
-                        if i == line.len() - 1 {
-                            let Some((x1, x2)) = current_digit else {
-                                panic!("Invalid digit");
-                            };
-                            let digit_slice = &line[x1..=x2];
-                            let value = digit_slice
-                                .parse::<u32>()
-                                .expect(&format!("Not a number: {digit_slice}"));
-                            row.push(Element::PartNumber {
-                                value,
-                                start: (x1, y).into(),
-                                end: (x2, y).into(),
-                            });
-                            current_digit = None;
-                        }
-                        continue;
+        let width = input.lines().map(str::len).max().unwrap_or(0);
+        let mut elements = vec![];
+        let mut cells = vec![];
+
+        for (y, line) in input.lines().enumerate() {
+            let mut row = vec![None; width];
+            let chars: Vec<char> = line.chars().collect();
+            let mut x = 0;
+
+            while x < chars.len() {
+                let id = Some(elements.len());
+
+                if chars[x].is_ascii_digit() {
+                    let start = x;
+                    while x < chars.len() && chars[x].is_ascii_digit() {
+                        row[x] = id;
+                        x += 1;
                     }
+                    let digits: String = chars[start..x].iter().collect();
+                    let value = digits
+                        .parse::<u32>()
+                        .unwrap_or_else(|_| panic!("Not a number: {digits}"));
+                    elements.push(Element::PartNumber {
+                        value,
+                        start: (start, y).into(),
+                        end: (x - 1, y).into(),
+                    });
+                    continue;
+                }
 
-                    if let Some((x1, x2)) = current_digit {
-                        let digit_slice = &line[x1..=x2];
-                        let value = digit_slice
-                            .parse::<u32>()
-                            .expect(&format!("Not a number: {digit_slice}"));
-                        row.push(Element::PartNumber {
-                            value,
-                            start: (x1, y).into(),
-                            end: (x2, y).into(),
-                        });
-                        current_digit = None;
+                let coordinate = Coordinate { x, y };
+                elements.push(if chars[x] == '.' {
+                    Element::Empty { coordinate }
+                } else {
+                    Element::Symbol {
+                        character: chars[x],
+                        coordinate,
                     }
+                });
+                row[x] = id;
+                x += 1;
+            }
 
-                    let element = {
-                        if current_char == '.' {
-                            Element::Empty { coordinate }
-                        } else {
-                            Element::Symbol {
-                                character: current_char,
-                                coordinate,
-                            }
-                        }
-                    };
-                    row.push(element);
-                }
+            cells.extend(row);
+        }
 
-                row
-            })
-            .collect();
+        Self {
+            elements,
+            width,
+            cells,
+        }
+    }
 
-        Self { elements }
+    fn id(&self, coordinate: Coordinate) -> Option<usize> {
+        if coordinate.x >= self.width {
+            return None;
+        }
+        *self.cells.get(coordinate.y * self.width + coordinate.x)?
     }
 
     fn get(&self, coordinate: Coordinate) -> Option<&Element> {
-        self.elements.iter().find(|el| el.is_at(coordinate))
+        let el = &self.elements[self.id(coordinate)?];
+        debug_assert!(el.is_at(coordinate));
+        Some(el)
     }
 
+    /// Non-empty elements touching the element at `coordinate`. Numbers spanning several adjacent cells are
+    /// listed once.
     fn adjacent_elements(&self, coordinate: Coordinate) -> Vec<&Element> {
         let Some(el) = self.get(coordinate) else {
             return vec![];
         };
 
-        el.adjacent_coords()
-            .into_iter()
-            .flat_map(|c| {
-                let el = self.get(c)?;
-                if el.is_empty() {
-                    None
-                } else {
-                    return Some(el);
-                }
-            })
-            .collect::<HashSet<_>>()
+        let mut ids: Vec<usize> = el
+            .adjacent_coords()
             .into_iter()
-            .collect()
+            .filter_map(|c| self.id(c))
+            .filter(|&id| !self.elements[id].is_empty())
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        ids.into_iter().map(|id| &self.elements[id]).collect()
     }
 
     fn numbers(&self) -> Vec<&Element> {
@@ -240,7 +228,6 @@ pub fn part_one(input: &str) -> Option<u32> {
                 {
                     Some(*value)
                 } else {
-                    // dbg!(el);
                     None
                 }
             })
@@ -276,9 +263,129 @@ pub fn part_two(input: &str) -> Option<u32> {
     )
 }
 
+const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '@', '=', '%', '&', '-'];
+
+/// Generates a `size`×`size` schematic of numbers with up to three digits, symbols and empty cells.
+fn generate_input(rng: &mut StdRng, size: usize) -> String {
+    let mut input = String::new();
+
+    for _ in 0..size {
+        let mut row = String::new();
+        while row.len() < size {
+            match rng.random_range(0..10) {
+                0 | 1 => {
+                    let len = rng.random_range(1..=3).min(size - row.len());
+                    row.extend((0..len).map(|_| char::from(rng.random_range(b'0'..=b'9'))));
+                }
+                // gears are over-represented to get interesting part two inputs.
+                2 => row.push('*'),
+                3 => row.push(*SYMBOLS.choose(rng).unwrap()),
+                _ => {}
+            }
+            if row.len() < size {
+                row.push('.');
+            }
+        }
+        input.push_str(&row);
+        input.push('\n');
+    }
+
+    input
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::generate::compare;
+    use advent_of_code::template::viz::{Colour, Frame};
+
+    fn line(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
+        if start.y != end.y {
+            panic!("Not a horizonal line");
+        }
+        (start.x..=end.x)
+            .map(move |x| (x, start.y).into())
+            .collect()
+    }
+
+    /// Naive reference: scans the neighbourhood of every number directly on the character grid.
+    fn reference(input: &str) -> (Option<u32>, Option<u32>) {
+        let grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+        let mut numbers = vec![];
+
+        for (y, row) in grid.iter().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                if row[x].is_ascii_digit() {
+                    let start = x;
+                    while x < row.len() && row[x].is_ascii_digit() {
+                        x += 1;
+                    }
+                    let value: u32 = row[start..x].iter().collect::<String>().parse().unwrap();
+                    numbers.push((value, y, start, x - 1));
+                } else {
+                    x += 1;
+                }
+            }
+        }
+
+        let is_adjacent = |&(_, y, x1, x2): &(u32, usize, usize, usize),
+                           (gx, gy): (usize, usize)| {
+            gy + 1 >= y && gy <= y + 1 && gx + 1 >= x1 && gx <= x2 + 1
+        };
+
+        let symbols: Vec<(char, usize, usize)> = grid
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, c)| (*c, x, y)))
+            .filter(|(c, _, _)| *c != '.' && !c.is_ascii_digit())
+            .collect();
+
+        let part_one = numbers
+            .iter()
+            .filter(|n| symbols.iter().any(|(_, x, y)| is_adjacent(n, (*x, *y))))
+            .map(|n| n.0)
+            .sum();
+
+        let part_two = symbols
+            .iter()
+            .filter(|(c, _, _)| *c == '*')
+            .filter_map(|(_, x, y)| {
+                let adjacent: Vec<_> = numbers
+                    .iter()
+                    .filter(|n| is_adjacent(n, (*x, *y)))
+                    .collect();
+                (adjacent.len() == 2).then(|| adjacent[0].0 * adjacent[1].0)
+            })
+            .sum();
+
+        (Some(part_one), Some(part_two))
+    }
+
+    #[test]
+    fn test_adjacent_coords() {
+        let around = adjacent_coords((0, 0).into(), (1, 0).into());
+        assert_eq!(around.len(), 4);
+        let around = adjacent_coords((2, 3).into(), (4, 3).into());
+        assert_eq!(around.len(), 12);
+        assert!(!around.contains(&(3, 3).into()));
+    }
+
+    #[test]
+    fn test_map_lookup() {
+        let map = Map::new(&advent_of_code::template::read_file("examples", DAY));
+        let number = map.get((2, 0).into()).unwrap();
+        assert_eq!(Some(number), map.get((0, 0).into()));
+        assert!(matches!(number, Element::PartNumber { value: 467, .. }));
+        assert!(map.get((10, 0).into()).is_none());
+        assert_eq!(
+            map.adjacent_elements((3, 1).into())
+                .iter()
+                .filter(|el| el.is_number())
+                .count(),
+            2
+        );
+    }
 
     #[test]
     fn test_part_one() {
@@ -291,4 +398,49 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(467835));
     }
+
+    #[test]
+    fn test_visualize_part_numbers() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let map = Map::new(&input);
+        let mut frame = Frame::new(&input);
+
+        for el in map.numbers() {
+            let Element::PartNumber { start, end, .. } = el else {
+                continue;
+            };
+            let is_part = map
+                .adjacent_elements(el.coord())
+                .into_iter()
+                .any(|el| el.is_symbol());
+            let colour = if is_part { Colour::Green } else { Colour::Red };
+            frame.highlight_region(line(*start, *end), colour);
+        }
+
+        let mask = frame.render_ascii();
+        let mask = mask.split("\n\n").nth(1).unwrap();
+        assert_eq!(mask.lines().next(), Some("GGG..RRR.."));
+        assert_eq!(mask.lines().nth(5), Some(".......RR."));
+        advent_of_code::assert_snapshot!("03-part-numbers", mask);
+    }
+
+    #[test]
+    fn test_against_reference() {
+        compare(
+            generate_input,
+            |i| reference(i).0,
+            part_one,
+            &[1, 5, 20],
+            10,
+            3,
+        );
+        compare(
+            generate_input,
+            |i| reference(i).1,
+            part_two,
+            &[1, 5, 20],
+            10,
+            3,
+        );
+    }
 }
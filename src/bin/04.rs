@@ -1,14 +1,46 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display, Formatter},
+};
 
 use itertools::Itertools;
 
-advent_of_code::solution!(4);
+advent_of_code::solution!(4, fuzz = [Card::parse], explain = explain);
+
+/// A set of numbers below 128, stored as a bitmask.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct NumberSet(u128);
+
+impl NumberSet {
+    fn insert(&mut self, n: u32) {
+        assert!(n < 128, "Number out of range: {n}");
+        self.0 |= 1 << n;
+    }
+
+    fn intersection(self, other: NumberSet) -> NumberSet {
+        NumberSet(self.0 & other.0)
+    }
+
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+}
+
+impl FromIterator<u32> for NumberSet {
+    fn from_iter<T: IntoIterator<Item = u32>>(iter: T) -> Self {
+        let mut set = NumberSet::default();
+        for n in iter {
+            set.insert(n);
+        }
+        set
+    }
+}
 
 #[derive(Debug, Clone)]
 struct Card {
     id: u32,
-    winning: Vec<u32>,
-    numbers: Vec<u32>,
+    winning: NumberSet,
+    numbers: NumberSet,
 }
 
 impl Card {
@@ -19,7 +51,7 @@ impl Card {
             .unwrap()
             .trim_start()
             .parse::<u32>()
-            .expect(&format!("Invalid digit at {line}"));
+            .unwrap_or_else(|_| panic!("Invalid digit at {line}"));
         let (winning, numbers) = rest
             .split(" | ")
             .map(|nums| {
@@ -37,58 +69,190 @@ impl Card {
         }
     }
 
-    fn score(&self) -> u32 {
-        self.numbers
-            .iter()
-            .filter(|n| self.winning.contains(n))
-            .count() as u32
+    /// Number of own numbers that are winning numbers.
+    fn matches(&self) -> u32 {
+        self.winning.intersection(self.numbers).len()
     }
 
-    fn copies(&self) -> Vec<u32> {
-        let score = self.score();
-        ((self.id + 1)..=(self.id + score)).collect()
+    fn points(&self) -> u32 {
+        match self.matches() {
+            0 => 0,
+            matches => 2u32.pow(matches - 1),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct IdError {
+    expected: u32,
+    found: u32,
+}
+
+impl Display for IdError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "card ids have to be contiguous starting at 1: expected card {} but found card {}",
+            self.expected, self.found
+        )
     }
 }
 
+/// Parses all cards and checks that their ids are `1, 2, 3, …` in order, as copies are won by id.
+fn parse(input: &str) -> Result<Vec<Card>, IdError> {
+    let cards: Vec<Card> = input.lines().map(Card::parse).collect();
+    for (expected, card) in (1..).zip(&cards) {
+        if card.id != expected {
+            return Err(IdError {
+                expected,
+                found: card.id,
+            });
+        }
+    }
+    Ok(cards)
+}
+
+/// How a card fared after all copies have been won.
+#[derive(Debug, PartialEq)]
+struct Tally {
+    id: u32,
+    matches: u32,
+    /// Instances of the card, including the original.
+    copies: u32,
+}
+
+/// Plays all cards in order. Copies only ever go to the next `matches` cards, so the copies still to be won are
+/// kept in a window that slides along with the current card. Copies of cards past the end are dropped.
+fn cascade(cards: &[Card]) -> Vec<Tally> {
+    let mut won: VecDeque<u32> = VecDeque::new();
+
+    cards
+        .iter()
+        .map(|card| {
+            let copies = 1 + won.pop_front().unwrap_or(0);
+            let matches = card.matches();
+            if won.len() < matches as usize {
+                won.resize(matches as usize, 0);
+            }
+            for next in won.iter_mut().take(matches as usize) {
+                *next += copies;
+            }
+
+            Tally {
+                id: card.id,
+                matches,
+                copies,
+            }
+        })
+        .collect()
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
     Some(
         input
             .lines()
             .map(Card::parse)
-            .flat_map(|card| {
-                let score = card.score();
-                if score > 0 {
-                    Some(2u32.pow((score - 1) as u32))
-                } else {
-                    None
-                }
-            })
+            .map(|card| card.points())
             .sum(),
     )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut cards = BTreeMap::new();
-    input.lines().for_each(|line| {
-        let card = Card::parse(line);
-        cards.entry(card.id).and_modify(|e| *e += 1).or_insert(1);
-        let copies = card.copies();
-        let weight = *cards.get(&card.id).unwrap();
-        for copy in copies.iter() {
-            cards
-                .entry(*copy)
-                .and_modify(|e| *e += weight)
-                .or_insert(weight);
+    let cards = parse(input).unwrap_or_else(|e| panic!("{e}"));
+    Some(cascade(&cards).iter().map(|tally| tally.copies).sum())
+}
+
+/// Prints the matches, points and copies of the card with the id given as `value`, or of all cards for `all`.
+fn explain(input: &str, value: &str) {
+    let cards = match parse(input) {
+        Ok(cards) => cards,
+        Err(e) => {
+            eprintln!("{e}");
+            return;
+        }
+    };
+    let id = match value {
+        "all" => None,
+        id => match id.parse::<u32>() {
+            Ok(id) if (1..=cards.len() as u32).contains(&id) => Some(id),
+            _ => {
+                eprintln!("`{value}` is not a card id between 1 and {}.", cards.len());
+                return;
+            }
+        },
+    };
+
+    let last = cards.len() as u32;
+    println!("card  matches  points  copies  wins copies of");
+    for (card, tally) in cards.iter().zip(cascade(&cards)) {
+        if id.is_some_and(|id| id != tally.id) {
+            continue;
         }
-    });
 
-    Some(cards.values().sum())
+        let wins = match (tally.id + 1, (tally.id + tally.matches).min(last)) {
+            (first, last) if first > last => "-".to_string(),
+            (first, last) if first == last => format!("{first} (+{})", tally.copies),
+            (first, last) => format!("{first}-{last} (+{} each)", tally.copies),
+        };
+        println!(
+            "{:>4}  {:>7}  {:>6}  {:>6}  {wins}",
+            tally.id,
+            tally.matches,
+            card.points(),
+            tally.copies
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_number_set() {
+        let a: NumberSet = [41, 48, 83, 86, 17].into_iter().collect();
+        let b: NumberSet = [83, 86, 6, 31, 17, 9, 48, 53].into_iter().collect();
+        assert_eq!(a.intersection(b).len(), 4);
+        assert_eq!(NumberSet::default().len(), 0);
+    }
+
+    #[test]
+    fn test_contiguous_ids() {
+        let input = "Card 1: 1 2 | 1 3\nCard 3: 1 | 2\n";
+        assert_eq!(
+            parse(input).err(),
+            Some(IdError {
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_cascade() {
+        let cards = parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(
+            cascade(&cards)
+                .iter()
+                .map(|t| (t.id, t.matches, t.copies))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 4, 1),
+                (2, 2, 2),
+                (3, 2, 4),
+                (4, 1, 8),
+                (5, 0, 14),
+                (6, 0, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_copies_past_the_end_are_dropped() {
+        let cards = parse("Card 1: 1 2 | 1 2\nCard 2: 1 | 1\n").unwrap();
+        assert_eq!(cascade(&cards).iter().map(|t| t.copies).sum::<u32>(), 3);
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
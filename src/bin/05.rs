@@ -1,3 +1,9 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
+
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -7,8 +13,8 @@ use nom::{
     sequence::{preceded, separated_pair, terminated, tuple},
     IResult, Parser,
 };
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-advent_of_code::solution!(5);
+use rand::{rngs::StdRng, RngExt};
+advent_of_code::solution!(5, generate = generate_input, explain = explain);
 
 #[derive(Debug, PartialEq)]
 struct Seeds(Vec<u64>);
@@ -51,6 +57,30 @@ impl Map {
             .contains(&val)
             .then(|| (val - self.source_start) + self.dest_start)
     }
+
+    /// Maps a destination value back to its source value.
+    fn invert(&self, val: u64) -> Option<u64> {
+        (self.dest_start..self.dest_start.saturating_add(self.len))
+            .contains(&val)
+            .then(|| (val - self.dest_start) + self.source_start)
+    }
+
+    /// Splits `range` into the part covered by this map, translated to the destination, and the uncovered rest.
+    fn process_range(&self, range: &Range<u64>) -> (Option<Range<u64>>, Vec<Range<u64>>) {
+        let start = range.start.max(self.source_start);
+        let end = range.end.min(self.source_start.saturating_add(self.len));
+        if start >= end {
+            return (None, vec![range.clone()]);
+        }
+
+        let mapped = (start - self.source_start + self.dest_start)
+            ..(end - self.source_start + self.dest_start);
+        let rest = [range.start..start, end..range.end]
+            .into_iter()
+            .filter(|r| !r.is_empty())
+            .collect();
+        (Some(mapped), rest)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -74,62 +104,354 @@ impl MapSection {
         Ok((input, MapSection { source, dest, map }))
     }
 
+    /// Returns the first map covering `val`, if any.
+    fn find(&self, val: u64) -> Option<&Map> {
+        self.map.iter().find(|m| m.process(val).is_some())
+    }
+
     fn process(&self, val: u64) -> u64 {
         self.map.iter().find_map(|m| m.process(val)).unwrap_or(val)
     }
+
+    /// Returns all source values that `process` maps to `val`, in ascending order.
+    /// A value can have several sources, e.g. one covered by a map and itself if it is not covered.
+    fn invert(&self, val: u64) -> Vec<u64> {
+        let mut sources: Vec<u64> = self
+            .map
+            .iter()
+            .filter_map(|m| m.invert(val))
+            .chain([val])
+            .filter(|&source| self.process(source) == val)
+            .collect();
+        sources.sort_unstable();
+        sources.dedup();
+        sources
+    }
+
+    /// Maps every range through the section. Like `process`, the first matching map wins and uncovered values
+    /// are passed through unchanged.
+    fn process_ranges(&self, ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+        let mut mapped = vec![];
+        let mut pending = ranges.to_vec();
+
+        for map in &self.map {
+            pending = pending
+                .iter()
+                .flat_map(|range| {
+                    let (covered, rest) = map.process_range(range);
+                    mapped.extend(covered);
+                    rest
+                })
+                .collect();
+        }
+
+        mapped.extend(pending);
+        mapped
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ChainError {
+    /// No section maps from the category.
+    Missing(String),
+    /// More than one section maps from the category.
+    Duplicate(String),
+}
+
+impl Display for ChainError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::Missing(category) => write!(
+                f,
+                "broken almanac: no map from `{category}` on the way from `seed` to `location`"
+            ),
+            ChainError::Duplicate(category) => {
+                write!(f, "ambiguous almanac: multiple maps from `{category}`")
+            }
+        }
+    }
+}
+
+/// A value in one category on its way from `seed` to `location`.
+#[derive(Debug, PartialEq)]
+struct Step<'a> {
+    category: &'a str,
+    value: u64,
+    /// The map that produced the value, `None` if the previous value was passed through unchanged.
+    map: Option<&'a Map>,
+}
+
+#[derive(Debug)]
+struct Almanac {
+    seeds: Seeds,
+    sections: Vec<MapSection>,
 }
 
-fn parse_inner(input: &str) -> IResult<&str, (Seeds, Vec<MapSection>)> {
+impl Almanac {
+    /// Orders the sections by following their `source` and `dest` names from `seed` to `location`.
+    /// Sections that are not part of the chain are ignored.
+    fn chain(&self) -> Result<Vec<&MapSection>, ChainError> {
+        let mut by_source: HashMap<&str, &MapSection> = HashMap::new();
+        for section in &self.sections {
+            if by_source.insert(&section.source, section).is_some() {
+                return Err(ChainError::Duplicate(section.source.clone()));
+            }
+        }
+
+        let mut chain = vec![];
+        let mut category = "seed";
+        while category != "location" {
+            // sections are removed once used, so a cycle ends up as a missing section.
+            let section = by_source
+                .remove(category)
+                .ok_or_else(|| ChainError::Missing(category.to_string()))?;
+            chain.push(section);
+            category = &section.dest;
+        }
+
+        Ok(chain)
+    }
+
+    /// Follows `seed` through every category and lists the intermediate values.
+    fn trace(&self, seed: u64) -> Result<Vec<Step<'_>>, ChainError> {
+        let mut steps = vec![Step {
+            category: "seed",
+            value: seed,
+            map: None,
+        }];
+        let mut value = seed;
+
+        for section in self.chain()? {
+            let map = section.find(value);
+            value = section.process(value);
+            steps.push(Step {
+                category: &section.dest,
+                value,
+                map,
+            });
+        }
+
+        Ok(steps)
+    }
+
+    /// Returns all seeds that end up at `location`, in ascending order. The seeds do not have to be listed in
+    /// the almanac.
+    fn seeds_for(&self, location: u64) -> Result<Vec<u64>, ChainError> {
+        let seeds = self
+            .chain()?
+            .iter()
+            .rev()
+            .fold(vec![location], |values, section| {
+                values
+                    .iter()
+                    .flat_map(|&value| section.invert(value))
+                    .sorted_unstable()
+                    .dedup()
+                    .collect()
+            });
+        Ok(seeds)
+    }
+
+    /// Whether `seed` is part of one of the seed ranges of part two.
+    fn in_seed_ranges(&self, seed: u64) -> bool {
+        self.seeds
+            .0
+            .iter()
+            .tuples()
+            .any(|(&start, &len)| (start..start.saturating_add(len)).contains(&seed))
+    }
+}
+
+fn parse_inner(input: &str) -> IResult<&str, Almanac> {
     all_consuming(terminated(
         tuple((
             terminated(Seeds::parse, multispace1),
             separated_list1(multispace1, MapSection::parse),
         )),
         newline,
-    ))(input)
+    ))
+    .map(|(seeds, sections)| Almanac { seeds, sections })
+    .parse(input)
 }
 
-fn parse(input: &str) -> (Seeds, Vec<MapSection>) {
+fn parse(input: &str) -> Almanac {
     let (_, res) = parse_inner(input).unwrap();
     res
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let (seeds, maps) = parse(input);
-    seeds
+    let almanac = parse(input);
+    let chain = almanac.chain().unwrap_or_else(|e| panic!("{e}"));
+    almanac
+        .seeds
         .0
-        .into_iter()
-        .map(|mut seed| {
-            for map in maps.iter() {
-                seed = map.process(seed);
-            }
-            seed
+        .iter()
+        .map(|&seed| {
+            chain
+                .iter()
+                .fold(seed, |value, section| section.process(value))
         })
         .min()
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let (seeds, maps) = parse(input);
-    let seeds = seeds
+    let almanac = parse(input);
+    let chain = almanac.chain().unwrap_or_else(|e| panic!("{e}"));
+    let seeds = almanac
+        .seeds
         .0
-        .into_iter()
+        .iter()
         .tuples()
-        .flat_map(|(start, delta)| start..start + delta)
+        .map(|(&start, &len)| start..start.saturating_add(len))
+        .filter(|r| !r.is_empty())
         .collect::<Vec<_>>();
-    seeds
-        .into_par_iter()
-        .map(|mut seed| {
-            for map in maps.iter() {
-                seed = map.process(seed);
+    chain
+        .iter()
+        .fold(seeds, |ranges, section| section.process_ranges(&ranges))
+        .iter()
+        .map(|r| r.start)
+        .min()
+}
+
+/// Prints the path of a seed through every category and all seeds that lead to the same location.
+fn explain(input: &str, value: &str) {
+    const MAX_SEEDS: usize = 10;
+
+    let Ok(seed) = value.parse::<u64>() else {
+        eprintln!("`{value}` is not a valid seed.");
+        return;
+    };
+    let almanac = parse(input);
+    let steps = match almanac.trace(seed) {
+        Ok(steps) => steps,
+        Err(e) => {
+            eprintln!("{e}");
+            return;
+        }
+    };
+    let location = steps.last().map_or(seed, |s| s.value);
+    // the chain has already been validated by `trace`.
+    let seeds = almanac.seeds_for(location).unwrap_or_default();
+
+    let listed = if almanac.seeds.0.contains(&seed) {
+        "listed in the almanac"
+    } else {
+        "not listed in the almanac"
+    };
+    let in_ranges = if almanac.in_seed_ranges(seed) {
+        "part of"
+    } else {
+        "outside of"
+    };
+    println!("Seed {seed} is {listed} and {in_ranges} the seed ranges of part two.\n");
+
+    let width = steps.iter().map(|s| s.category.len()).max().unwrap_or(0);
+    for step in &steps {
+        let via = step.map.map_or(String::new(), |m| {
+            format!("  via {} {} {}", m.dest_start, m.source_start, m.len)
+        });
+        println!("{:width$}  {}{via}", step.category, step.value);
+    }
+
+    let shown = seeds.iter().take(MAX_SEEDS).join(", ");
+    let more = seeds.len().saturating_sub(MAX_SEEDS);
+    let more = if more > 0 {
+        format!(" and {more} more")
+    } else {
+        String::new()
+    };
+    println!("\nLocation {location} is reached from seed(s) {shown}{more}.");
+}
+
+/// Alphabetic name for the `i`-th intermediate category, as the parser only accepts letters.
+fn category_name(i: usize) -> String {
+    (0..3)
+        .map(|d| char::from(b'a' + (i / 26usize.pow(d) % 26) as u8))
+        .collect::<String>()
+        + "category"
+}
+
+/// Generates an almanac with four seed ranges and `size` chained map sections from `seed` to `location`.
+fn generate_input(rng: &mut StdRng, size: usize) -> String {
+    const LIMIT: u64 = 1200;
+
+    let seeds = (0..4)
+        .map(|_| format!("{} {}", rng.random_range(0..1000), rng.random_range(1..=50)))
+        .join(" ");
+
+    let names: Vec<String> = (0..=size)
+        .map(|i| match i {
+            0 => "seed".to_string(),
+            i if i == size => "location".to_string(),
+            i => category_name(i),
+        })
+        .collect();
+
+    let sections = names
+        .iter()
+        .tuple_windows()
+        .map(|(source, dest)| {
+            let mut lines = vec![format!("{source}-to-{dest} map:")];
+            let mut start = rng.random_range(0..50);
+            // source ranges of a section never overlap.
+            while start < LIMIT && lines.len() < 9 {
+                let len = rng.random_range(1..=150);
+                lines.push(format!("{} {start} {len}", rng.random_range(0..LIMIT)));
+                start += len + rng.random_range(0..50);
             }
-            seed
+            lines.join("\n")
         })
-        .min()
+        .join("\n\n");
+
+    format!("seeds: {seeds}\n\n{sections}\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::generate::compare;
+
+    /// Naive reference: maps every single seed through the sections in file order.
+    fn reference(input: &str, ranges: bool) -> Option<u64> {
+        let mut blocks = input.split("\n\n");
+        let seeds: Vec<u64> = blocks
+            .next()
+            .unwrap()
+            .split_whitespace()
+            .skip(1)
+            .map(|n| n.parse().unwrap())
+            .collect();
+        let seeds: Vec<u64> = if ranges {
+            seeds.chunks(2).flat_map(|c| c[0]..c[0] + c[1]).collect()
+        } else {
+            seeds
+        };
+
+        let sections: Vec<Vec<Vec<u64>>> = blocks
+            .map(|block| {
+                block
+                    .lines()
+                    .skip(1)
+                    .map(|l| l.split(' ').map(|n| n.parse().unwrap()).collect())
+                    .collect()
+            })
+            .collect();
+
+        seeds
+            .into_iter()
+            .map(|mut value| {
+                for section in &sections {
+                    if let Some(range) = section
+                        .iter()
+                        .find(|r| (r[1]..r[1] + r[2]).contains(&value))
+                    {
+                        value = value - range[1] + range[0];
+                    }
+                }
+                value
+            })
+            .min()
+    }
 
     #[test]
     fn test_parse_seeds() {
@@ -218,6 +540,108 @@ mod tests {
         assert_eq!(map_section.process(2000), 2000);
     }
 
+    #[test]
+    fn test_map_section_ranges() {
+        let input = r#"light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13"#;
+        let (_, map_section) = MapSection::parse(input).unwrap();
+        let mut ranges = map_section.process_ranges(&[40..50, 70..80, 95..105]);
+        ranges.sort_by_key(|r| r.start);
+        assert_eq!(
+            ranges,
+            vec![40..45, 45..48, 63..68, 74..81, 81..86, 100..105]
+        );
+    }
+
+    #[test]
+    fn test_map_section_invert() {
+        let input = r#"seed-to-soil map:
+50 98 2
+52 50 48"#;
+        let (_, map_section) = MapSection::parse(input).unwrap();
+        assert_eq!(map_section.invert(81), vec![79]);
+        assert_eq!(map_section.invert(51), vec![99]);
+        // 50 itself is mapped to 52, so only 98 leads to it.
+        assert_eq!(map_section.invert(50), vec![98]);
+        assert_eq!(map_section.invert(10), vec![10]);
+        // 98 is produced by `52 50 48` from 96, while 98 itself is mapped elsewhere.
+        assert_eq!(map_section.invert(98), vec![96]);
+    }
+
+    #[test]
+    fn test_trace() {
+        let almanac = parse(&advent_of_code::template::read_file("examples", DAY));
+        let steps = almanac.trace(79).unwrap();
+        assert_eq!(
+            steps
+                .iter()
+                .map(|s| (s.category, s.value))
+                .collect::<Vec<_>>(),
+            vec![
+                ("seed", 79),
+                ("soil", 81),
+                ("fertilizer", 81),
+                ("water", 81),
+                ("light", 74),
+                ("temperature", 78),
+                ("humidity", 78),
+                ("location", 82)
+            ]
+        );
+        assert_eq!(
+            steps[1].map,
+            Some(&Map {
+                source_start: 50,
+                dest_start: 52,
+                len: 48
+            })
+        );
+        assert_eq!(steps[2].map, None);
+    }
+
+    #[test]
+    fn test_reverse_search() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let almanac = parse(&input);
+
+        let seeds = almanac.seeds_for(part_one(&input).unwrap()).unwrap();
+        assert!(seeds.contains(&13));
+
+        let seeds = almanac.seeds_for(part_two(&input).unwrap()).unwrap();
+        assert!(seeds.iter().any(|&seed| almanac.in_seed_ranges(seed)));
+        for seed in seeds {
+            assert_eq!(almanac.trace(seed).unwrap().last().unwrap().value, 46);
+        }
+    }
+
+    #[test]
+    fn test_chain_follows_names() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let mut blocks: Vec<&str> = input.trim_end().split("\n\n").collect();
+        blocks[1..].reverse();
+        let shuffled = blocks.join("\n\n") + "\n";
+
+        assert_eq!(part_one(&shuffled), Some(35));
+        assert_eq!(part_two(&shuffled), Some(46));
+    }
+
+    #[test]
+    fn test_broken_chain() {
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n\nwater-to-location map:\n1 2 3\n";
+        assert_eq!(
+            parse(input).chain().unwrap_err(),
+            ChainError::Missing("soil".to_string())
+        );
+
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n\nseed-to-location map:\n1 2 3\n";
+        assert_eq!(
+            parse(input).chain().unwrap_err(),
+            ChainError::Duplicate("seed".to_string())
+        );
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -229,4 +653,24 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(46));
     }
+
+    #[test]
+    fn test_against_reference() {
+        compare(
+            generate_input,
+            |i| reference(i, false),
+            part_one,
+            &[1, 3, 7, 15],
+            10,
+            5,
+        );
+        compare(
+            generate_input,
+            |i| reference(i, true),
+            part_two,
+            &[1, 3, 7, 15],
+            10,
+            5,
+        );
+    }
 }
@@ -1,3 +1,6 @@
+use std::ops::RangeInclusive;
+
+use advent_of_code::math;
 use nom::{
     bytes::complete::tag,
     character::complete::{self, newline, space1},
@@ -15,22 +18,16 @@ struct Race {
 }
 
 impl Race {
-    fn solve(&self) -> u64 {
-        let first = (1..self.time)
-            .into_iter()
-            .find(|t| self.possible_win(t))
-            .unwrap();
-        let last = (1..self.time)
-            .into_iter()
-            .rev()
-            .find(|t| self.possible_win(t))
-            .unwrap();
-
-        last + 1 - first
+    /// Returns the hold times that beat the record, if any.
+    /// Holding for `t` wins if `t * (time - t) > distance`, i.e. if `t² - time * t + distance < 0`.
+    fn winning_hold_times(&self) -> Option<RangeInclusive<u64>> {
+        let times = math::quadratic_below(1, -i128::from(self.time), i128::from(self.distance))?;
+        Some(*times.start() as u64..=*times.end() as u64)
     }
 
-    fn possible_win(&self, t: &u64) -> bool {
-        t * (self.time - t) > self.distance
+    fn solve(&self) -> u64 {
+        self.winning_hold_times()
+            .map_or(0, |times| times.end() - times.start() + 1)
     }
 }
 
@@ -88,6 +85,19 @@ pub fn part_two(input: &str) -> Option<u64> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_winning_hold_times() {
+        let race = |time, distance| Race { time, distance };
+        assert_eq!(race(7, 9).winning_hold_times(), Some(2..=5));
+        assert_eq!(race(30, 200).winning_hold_times(), Some(11..=19));
+        assert_eq!(race(4, 4).winning_hold_times(), None);
+        // only the exact optimum beats the record, which floating point roots cannot tell apart.
+        assert_eq!(
+            race(4_000_000_000, 3_999_999_999_999_999_999).winning_hold_times(),
+            Some(2_000_000_000..=2_000_000_000)
+        );
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
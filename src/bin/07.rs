@@ -1,4 +1,11 @@
-advent_of_code::solution!(7);
+advent_of_code::solution!(
+    7,
+    fuzz = [parse, parse_joker],
+    generate = generate_input,
+    variants = [2 => part_two_replacing]
+);
+
+use std::collections::HashSet;
 
 use itertools::Itertools;
 use nom::{
@@ -8,85 +15,52 @@ use nom::{
     sequence::preceded,
     IResult,
 };
+use rand::{rngs::StdRng, seq::IndexedRandom, RngExt};
+
+/// Rules of a Camel Cards variant.
+#[derive(Debug)]
+struct Rules {
+    /// Faces ordered from weakest to strongest.
+    order: &'static str,
+    /// Faces that act as whichever card makes the hand strongest.
+    wildcards: &'static str,
+    /// Classifies the group sizes of a hand, sorted in descending order, e.g. `[3, 2]` for a full house.
+    classify: fn(&[usize]) -> HandType,
+}
+
+const STANDARD: Rules = Rules {
+    order: "23456789TJQKA",
+    wildcards: "",
+    classify: HandType::classify,
+};
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+const JOKERS: Rules = Rules {
+    order: "J23456789TQKA",
+    wildcards: "J",
+    classify: HandType::classify,
+};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 struct Card {
-    face: char,
     value: u32,
+    face: char,
 }
 
 impl Card {
-    fn parse(face: char) -> Self {
-        if let Some(value) = face.to_digit(10) {
-            return Self { face, value };
-        }
-        let value = match face {
-            'T' => 10,
-            'J' => 11,
-            'Q' => 12,
-            'K' => 13,
-            'A' => 14,
-            c => panic!("Invalid value: {c}"),
-        };
-
-        Self { face, value }
-    }
-
-    fn parse_joker(face: char) -> Self {
-        if let Some(value) = face.to_digit(10) {
-            return Self { face, value };
+    fn parse(face: char, rules: &Rules) -> Self {
+        let value = rules
+            .order
+            .find(face)
+            .unwrap_or_else(|| panic!("Invalid value: {face}"));
+
+        Self {
+            value: value as u32,
+            face,
         }
-        let value = match face {
-            'J' => 1,
-            'T' => 10,
-            'Q' => 11,
-            'K' => 12,
-            'A' => 13,
-            c => panic!("Invalid value: {c}"),
-        };
-
-        Self { face, value }
-    }
-
-    fn is_joker(&self) -> bool {
-        self.face == 'J'
-    }
-
-    const fn new(face: char, value: u32) -> Self {
-        Self { face, value }
-    }
-
-    const fn all() -> [Card; 12] {
-        [
-            Card::new('2', 2),
-            Card::new('3', 3),
-            Card::new('4', 4),
-            Card::new('5', 5),
-            Card::new('6', 6),
-            Card::new('7', 7),
-            Card::new('8', 8),
-            Card::new('9', 9),
-            Card::new('T', 10),
-            Card::new('Q', 11),
-            Card::new('K', 12),
-            Card::new('A', 13),
-        ]
-    }
-}
-
-impl PartialOrd for Card {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.value.partial_cmp(&other.value)
-    }
-}
-
-impl Ord for Card {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.value.cmp(&other.value)
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 enum HandType {
     FiveOfAKind = 7,
     FourOfAKind = 6,
@@ -97,200 +71,206 @@ enum HandType {
     HighCard = 1,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl HandType {
+    fn classify(groups: &[usize]) -> Self {
+        match groups {
+            [5] => HandType::FiveOfAKind,
+            [4, ..] => HandType::FourOfAKind,
+            [3, 2] => HandType::FullHouse,
+            [3, ..] => HandType::ThreeOfAKind,
+            [2, 2, ..] => HandType::TwoPair,
+            [2, ..] => HandType::OnePair,
+            _ => HandType::HighCard,
+        }
+    }
+}
+
+/// Hands are ordered by their type first and by their cards from left to right second.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct Hand {
+    kind: HandType,
     cards: [Card; 5],
     bid: u32,
 }
 
 impl Hand {
-    fn hand_type(&self) -> HandType {
-        let counts = self.cards.iter().counts();
-        let counts = counts.values().sorted().collect::<Vec<_>>();
-        let res = match &counts[..] {
-            &[5] => HandType::FiveOfAKind,
-            &[1, 4] => HandType::FourOfAKind,
-            &[2, 3] => HandType::FullHouse,
-            &[1, 1, 3] => HandType::ThreeOfAKind,
-            &[1, 2, 2] => HandType::TwoPair,
-            &[1, 1, 1, 2] => HandType::OnePair,
-            _ => HandType::HighCard,
-        };
-        res
+    /// Wildcards always join the largest group of the other cards, which is never worse than any other choice.
+    fn hand_type(cards: &[Card], rules: &Rules) -> HandType {
+        let is_wild = |c: &&Card| rules.wildcards.contains(c.face);
+        let wild = cards.iter().filter(is_wild).count();
+
+        let mut groups = cards
+            .iter()
+            .filter(|c| !is_wild(c))
+            .counts()
+            .into_values()
+            .sorted_unstable_by(|a, b| b.cmp(a))
+            .collect::<Vec<_>>();
+        match groups.first_mut() {
+            Some(largest) => *largest += wild,
+            None => groups.push(wild),
+        }
+
+        (rules.classify)(&groups)
     }
 
-    fn parse(input: &str) -> IResult<&str, Self> {
+    fn parse<'a>(input: &'a str, rules: &Rules) -> IResult<&'a str, Self> {
         let (input, cards) = take(5usize)(input)?;
         let cards: [Card; 5] = cards
             .chars()
-            .into_iter()
-            .map(|c| Card::parse(c))
+            .map(|face| Card::parse(face, rules))
             .collect::<Vec<_>>()
             .try_into()
             .unwrap();
         let (input, bid) = preceded(space1, complete::u32)(input)?;
 
-        Ok((input, Self { cards, bid }))
+        Ok((
+            input,
+            Self {
+                kind: Hand::hand_type(&cards, rules),
+                cards,
+                bid,
+            },
+        ))
     }
 }
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let cmp = self.hand_type().cmp(&other.hand_type());
-        match cmp {
-            std::cmp::Ordering::Equal => self
-                .cards
-                .iter()
-                .zip(&other.cards)
-                .find_map(|(a, b)| {
-                    let card_cmp = a.cmp(&b);
-                    match card_cmp {
-                        std::cmp::Ordering::Equal => None,
-                        _ => Some(card_cmp),
-                    }
-                })
-                .unwrap(),
-            _ => cmp,
-        }
-    }
+fn parse_with<'a>(input: &'a str, rules: &Rules) -> IResult<&'a str, Vec<Hand>> {
+    separated_list1(newline, |i| Hand::parse(i, rules))(input)
 }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(&other))
-    }
+fn parse(input: &str) -> IResult<&str, Vec<Hand>> {
+    parse_with(input, &STANDARD)
 }
 
-fn count_compare(counts: &[&usize]) -> HandType {
-    match &counts[..] {
-        &[5] => HandType::FiveOfAKind,
-        &[1, 4] => HandType::FourOfAKind,
-        &[2, 3] => HandType::FullHouse,
-        &[1, 1, 3] => HandType::ThreeOfAKind,
-        &[1, 2, 2] => HandType::TwoPair,
-        &[1, 1, 1, 2] => HandType::OnePair,
-        _ => HandType::HighCard,
-    }
+fn parse_joker(input: &str) -> IResult<&str, Vec<Hand>> {
+    parse_with(input, &JOKERS)
 }
 
-fn cards_score(cards: &[Card]) -> HandType {
-    let counts = cards.iter().counts();
-    count_compare(&counts.values().sorted().collect::<Vec<_>>())
+fn total_winnings(hands: Vec<Hand>) -> u32 {
+    hands
+        .into_iter()
+        .sorted()
+        .enumerate()
+        .map(|(i, hand)| (i as u32 + 1) * hand.bid)
+        .sum()
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct JokerHand {
-    cards: [Card; 5],
-    bid: u32,
+pub fn part_one(input: &str) -> Option<u32> {
+    Some(total_winnings(parse(input).unwrap().1))
 }
 
-fn _display_cards(cards: &[Card]) -> String {
-    cards.iter().map(|c| c.face).join("")
+pub fn part_two(input: &str) -> Option<u32> {
+    Some(total_winnings(parse_joker(input).unwrap().1))
 }
 
-impl JokerHand {
-    fn hand_type(&self) -> HandType {
-        if !self.cards.iter().any(|c| c.is_joker()) {
-            // println!("{} has no jokers", display_cards(&self.cards));
-            return cards_score(&self.cards);
-        }
-
-        Card::all()
-            .iter()
-            .map(|replacement| {
-                let new = self
-                    .cards
-                    .clone()
-                    .into_iter()
-                    .map(|c| if c.is_joker() { replacement.clone() } else { c })
-                    .collect::<Vec<_>>();
-                // println!("{} -> {}", display_cards(&self.cards), display_cards(&new));
-                cards_score(&new)
-            })
-            .sorted()
-            .last()
-            .unwrap()
-    }
-
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (input, cards) = take(5usize)(input)?;
-        let cards: [Card; 5] = cards
-            .chars()
-            .into_iter()
-            .map(|c| Card::parse_joker(c))
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
-        let (input, bid) = preceded(space1, complete::u32)(input)?;
-
-        Ok((input, Self { cards, bid }))
-    }
+/// Replaces jokers by every other face and keeps the strongest hand type, instead of counting them.
+/// Compare with `cargo solve 7 --variants`.
+fn part_two_replacing(input: &str) -> Option<u32> {
+    let hands = parse_joker(input)
+        .unwrap()
+        .1
+        .into_iter()
+        .map(|hand| Hand {
+            kind: replaced_hand_type(&hand.cards),
+            ..hand
+        })
+        .collect();
+
+    Some(total_winnings(hands))
 }
 
-impl Ord for JokerHand {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let cmp = self.hand_type().cmp(&other.hand_type());
-        match cmp {
-            std::cmp::Ordering::Equal => self
-                .cards
+fn replaced_hand_type(cards: &[Card]) -> HandType {
+    FACES
+        .iter()
+        .map(|&face| {
+            let replaced: Vec<_> = cards
                 .iter()
-                .zip(&other.cards)
-                .find_map(|(a, b)| {
-                    let card_cmp = a.cmp(&b);
-                    match card_cmp {
-                        std::cmp::Ordering::Equal => None,
-                        _ => Some(card_cmp),
-                    }
+                .map(|card| match card.face {
+                    'J' => Card::parse(face, &JOKERS),
+                    _ => *card,
                 })
-                .unwrap(),
-            _ => cmp,
-        }
-    }
+                .collect();
+            Hand::hand_type(&replaced, &STANDARD)
+        })
+        .max()
+        .unwrap()
 }
 
-impl PartialOrd for JokerHand {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(&other))
-    }
-}
+const FACES: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
 
-fn parse(input: &str) -> IResult<&str, Vec<Hand>> {
-    separated_list1(newline, Hand::parse)(input)
-}
+/// Generates `size` distinct hands with random bids.
+/// Answers fit into `u32` for up to ~2500 hands.
+fn generate_input(rng: &mut StdRng, size: usize) -> String {
+    let mut seen = HashSet::new();
+    let mut input = String::new();
 
-fn parse_joker(input: &str) -> IResult<&str, Vec<JokerHand>> {
-    separated_list1(newline, JokerHand::parse)(input)
-}
-
-pub fn part_one(input: &str) -> Option<u32> {
-    Some(
-        parse(input)
-            .unwrap()
-            .1
-            .into_iter()
-            .sorted()
-            .enumerate()
-            .map(|(i, hand)| (i as u32 + 1) * hand.bid)
-            .sum(),
-    )
-}
+    while seen.len() < size {
+        let hand: String = (0..5).map(|_| *FACES.choose(rng).unwrap()).collect();
+        if seen.insert(hand.clone()) {
+            input.push_str(&format!("{hand} {}\n", rng.random_range(1..=999)));
+        }
+    }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    Some(
-        parse_joker(input)
-            .unwrap()
-            .1
-            .into_iter()
-            .sorted()
-            .enumerate()
-            .map(|(i, hand)| (i as u32 + 1) * hand.bid)
-            .sum(),
-    )
+    input
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::generate::compare;
+
+    fn reference_kind(cards: &str) -> u8 {
+        let mut counts = cards.chars().counts().into_values().collect::<Vec<_>>();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        match counts[..] {
+            [5] => 6,
+            [4, ..] => 5,
+            [3, 2] => 4,
+            [3, ..] => 3,
+            [2, 2, ..] => 2,
+            [2, ..] => 1,
+            _ => 0,
+        }
+    }
+
+    /// Naive reference: jokers are replaced by every other face and the best resulting hand type is used.
+    fn reference(input: &str, jokers: bool) -> Option<u32> {
+        let order = if jokers {
+            "J23456789TQKA"
+        } else {
+            "23456789TJQKA"
+        };
+
+        let mut hands = input
+            .lines()
+            .map(|line| {
+                let (cards, bid) = line.split_once(' ').unwrap();
+                let strength: Vec<_> = cards.chars().map(|c| order.find(c).unwrap()).collect();
+                let kind = if jokers {
+                    FACES
+                        .iter()
+                        .map(|r| reference_kind(&cards.replace('J', &r.to_string())))
+                        .max()
+                        .unwrap()
+                } else {
+                    reference_kind(cards)
+                };
+                (kind, strength, bid.parse::<u32>().unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        hands.sort();
+        Some(
+            hands
+                .iter()
+                .enumerate()
+                .map(|(i, (_, _, bid))| (i as u32 + 1) * bid)
+                .sum(),
+        )
+    }
 
     #[test]
     fn test_part_one() {
@@ -303,4 +283,74 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(5905));
     }
+
+    #[test]
+    fn test_part_two_replacing() {
+        let result = part_two_replacing(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(5905));
+        compare(
+            generate_input,
+            part_two,
+            part_two_replacing,
+            &[1, 10, 100],
+            10,
+            7,
+        );
+    }
+
+    fn kind(cards: &str, rules: &Rules) -> HandType {
+        let hand = format!("{cards} 1");
+        Hand::parse(&hand, rules).unwrap().1.kind
+    }
+
+    #[test]
+    fn test_hand_type() {
+        assert_eq!(kind("32T3K", &STANDARD), HandType::OnePair);
+        assert_eq!(kind("KTJJT", &STANDARD), HandType::TwoPair);
+        assert_eq!(kind("KTJJT", &JOKERS), HandType::FourOfAKind);
+        assert_eq!(kind("QQQJA", &JOKERS), HandType::FourOfAKind);
+        assert_eq!(kind("2345J", &JOKERS), HandType::OnePair);
+        assert_eq!(kind("22JJ3", &JOKERS), HandType::FourOfAKind);
+        assert_eq!(kind("JJJJJ", &JOKERS), HandType::FiveOfAKind);
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        // deuces are wild and there is no full house.
+        let rules = Rules {
+            order: "23456789TJQKA",
+            wildcards: "2",
+            classify: |groups| match groups {
+                [3, 2] => HandType::ThreeOfAKind,
+                groups => HandType::classify(groups),
+            },
+        };
+        assert_eq!(kind("2KKQQ", &rules), HandType::ThreeOfAKind);
+        assert_eq!(kind("22KQA", &rules), HandType::ThreeOfAKind);
+        // wildcards keep their own value when breaking ties.
+        assert_eq!(kind("2KKKK", &rules), HandType::FiveOfAKind);
+        assert!(
+            Hand::parse("2KKKK 1", &rules).unwrap().1 < Hand::parse("KKKKK 1", &rules).unwrap().1
+        );
+    }
+
+    #[test]
+    fn test_against_reference() {
+        compare(
+            generate_input,
+            |i| reference(i, false),
+            part_one,
+            &[1, 10, 100],
+            10,
+            7,
+        );
+        compare(
+            generate_input,
+            |i| reference(i, true),
+            part_two,
+            &[1, 10, 100],
+            10,
+            7,
+        );
+    }
 }
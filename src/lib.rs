@@ -1,4 +1,6 @@
 mod day;
+pub mod matcher;
+pub mod math;
 pub mod template;
 
 pub use day::*;
@@ -1,10 +1,19 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve};
+use advent_of_code::template::commands::{
+    all, check_secrets, countdown, dashboard, download, fuzz, profile, read, review, scaffold,
+    solve,
+};
 use args::{parse, AppArguments};
 
 mod args {
     use std::process;
 
-    use advent_of_code::Day;
+    use advent_of_code::{
+        template::{
+            config,
+            options::{parse_part, RunOptions},
+        },
+        Day,
+    };
 
     pub enum AppArguments {
         Download {
@@ -12,6 +21,12 @@ mod args {
         },
         Read {
             day: Day,
+            part: Option<u8>,
+            examples: bool,
+        },
+        Review {
+            accept: bool,
+            reject: bool,
         },
         Scaffold {
             day: Day,
@@ -19,37 +34,77 @@ mod args {
         Solve {
             day: Day,
             release: bool,
-            time: bool,
-            submit: Option<u8>,
+            options: RunOptions,
         },
         All {
             release: bool,
             time: bool,
         },
+        CheckSecrets,
+        Countdown,
+        Dashboard,
+        Fuzz {
+            day: Day,
+            iterations: Option<usize>,
+            seed: Option<u64>,
+            timeout: Option<u64>,
+        },
+        Profile {
+            day: Day,
+            part: Option<u8>,
+        },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
-        let app_args = match args.subcommand()?.as_deref() {
+        let mut app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
-                release: args.contains("--release"),
-                time: args.contains("--time"),
+                release: args.contains("--release") || config::get().defaults.release,
+                time: args.contains("--time") || config::get().defaults.time,
             },
+            Some("check-secrets") => AppArguments::CheckSecrets,
+            Some("countdown") => AppArguments::Countdown,
+            Some("dashboard") => AppArguments::Dashboard,
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
+            Some("fuzz") => AppArguments::Fuzz {
+                day: args.free_from_str()?,
+                iterations: args.opt_value_from_str("--iterations")?,
+                seed: args.opt_value_from_str("--seed")?,
+                timeout: args.opt_value_from_str("--timeout")?,
+            },
+            Some("profile") => AppArguments::Profile {
+                day: args.free_from_str()?,
+                part: args
+                    .opt_value_from_str::<_, String>("--part")?
+                    .map(|s| parse_part("--part", &s))
+                    .transpose()?,
+            },
             Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
+                part: args
+                    .opt_value_from_str::<_, String>("--part")?
+                    .map(|s| parse_part("--part", &s))
+                    .transpose()?,
+                examples: args.contains("--examples"),
+            },
+            Some("review") => AppArguments::Review {
+                accept: args.contains("--accept"),
+                reject: args.contains("--reject"),
             },
             Some("scaffold") => AppArguments::Scaffold {
                 day: args.free_from_str()?,
             },
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                time: args.contains("--time"),
+                release: args.contains("--release") || config::get().defaults.release,
+                options: {
+                    let mut options = RunOptions::parse_known(&mut args)?;
+                    options.time |= config::get().defaults.time;
+                    options
+                },
             },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
@@ -62,7 +117,14 @@ mod args {
         };
 
         let remaining = args.finish();
-        if !remaining.is_empty() {
+
+        if let AppArguments::Solve { options, .. } = &mut app_args {
+            // solutions may define arguments of their own, e.g. `--bag` on day 2.
+            options.extra = remaining
+                .into_iter()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect();
+        } else if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
         }
 
@@ -78,15 +140,35 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All { release, time } => all::handle(release, time),
+            AppArguments::CheckSecrets => check_secrets::handle(),
+            AppArguments::Countdown => countdown::handle(),
+            AppArguments::Dashboard => dashboard::handle(),
             AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
+            AppArguments::Fuzz {
+                day,
+                iterations,
+                seed,
+                timeout,
+            } => fuzz::handle(day, iterations, seed, timeout),
+            AppArguments::Profile { day, part } => profile::handle(day, part),
+            AppArguments::Read {
+                day,
+                part,
+                examples,
+            } => read::handle(day, part, examples),
+            AppArguments::Review { accept, reject } => {
+                if accept && reject {
+                    eprintln!("Error: `--accept` and `--reject` cannot be combined.");
+                    std::process::exit(1);
+                }
+                review::handle(accept, reject);
+            }
             AppArguments::Scaffold { day } => scaffold::handle(day),
             AppArguments::Solve {
                 day,
                 release,
-                time,
-                submit,
-            } => solve::handle(day, release, time, submit),
+                options,
+            } => solve::handle(day, release, &options),
         },
     };
 }
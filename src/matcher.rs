@@ -0,0 +1,250 @@
+/// Multi-pattern string matching based on the Aho–Corasick algorithm.
+/// Finds all (overlapping) occurrences of a set of patterns in a single pass over the input, from the front or
+/// from the back. Matching works on bytes, so offsets of matches always lie on UTF-8 character boundaries.
+use std::collections::VecDeque;
+
+const ROOT: usize = 0;
+
+/// An occurrence of a pattern. `start` and `end` are byte offsets into the haystack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// Index of the pattern in the order it was passed to [`Matcher::new`].
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Match {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Always `false`, as empty patterns never match.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// A trie over the patterns where missing transitions point along the failure links, so every byte of the
+/// haystack is consumed with a single lookup.
+#[derive(Debug, Clone)]
+struct Automaton {
+    transitions: Vec<[usize; 256]>,
+    /// Patterns ending at each node, including the ones reachable via failure links.
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Automaton {
+    fn new<'a>(patterns: impl Iterator<Item = &'a [u8]>) -> Self {
+        let mut transitions = vec![[ROOT; 256]];
+        let mut outputs = vec![vec![]];
+
+        for (id, pattern) in patterns.enumerate() {
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let mut node = ROOT;
+            for &byte in pattern {
+                // the root is never a child, so it doubles as the marker for missing edges.
+                if transitions[node][byte as usize] == ROOT {
+                    transitions.push([ROOT; 256]);
+                    outputs.push(vec![]);
+                    transitions[node][byte as usize] = transitions.len() - 1;
+                }
+                node = transitions[node][byte as usize];
+            }
+            outputs[node].push(id);
+        }
+
+        let mut fail = vec![ROOT; transitions.len()];
+        let mut queue: VecDeque<usize> = transitions[ROOT]
+            .iter()
+            .copied()
+            .filter(|&child| child != ROOT)
+            .collect();
+
+        // breadth first, so the failure target of a node is always complete before the node itself.
+        while let Some(node) = queue.pop_front() {
+            let inherited = outputs[fail[node]].clone();
+            outputs[node].extend(inherited);
+
+            let fallback = transitions[fail[node]];
+            for (next, &fallback) in transitions[node].iter_mut().zip(&fallback) {
+                if *next == ROOT {
+                    *next = fallback;
+                } else {
+                    fail[*next] = fallback;
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        Self {
+            transitions,
+            outputs,
+        }
+    }
+
+    /// Feeds `bytes` through the automaton and yields `(index, pattern)` for every pattern ending at `index`.
+    fn scan<'a>(
+        &'a self,
+        bytes: impl Iterator<Item = u8> + 'a,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        bytes
+            .enumerate()
+            .scan(ROOT, |state, (i, byte)| {
+                *state = self.transitions[*state][byte as usize];
+                Some((i, *state))
+            })
+            .flat_map(|(i, state)| self.outputs[state].iter().map(move |&id| (i, id)))
+    }
+}
+
+/// Finds occurrences of a fixed set of patterns. Empty patterns never match.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    forward: Automaton,
+    /// Automaton over the reversed patterns, used to scan from the end of the haystack.
+    backward: Automaton,
+    lengths: Vec<usize>,
+}
+
+impl Matcher {
+    #[must_use]
+    pub fn new<P: AsRef<str>>(patterns: impl IntoIterator<Item = P>) -> Self {
+        let patterns: Vec<Vec<u8>> = patterns
+            .into_iter()
+            .map(|p| p.as_ref().as_bytes().to_vec())
+            .collect();
+        let reversed: Vec<Vec<u8>> = patterns
+            .iter()
+            .map(|p| p.iter().rev().copied().collect())
+            .collect();
+
+        Self {
+            forward: Automaton::new(patterns.iter().map(Vec::as_slice)),
+            backward: Automaton::new(reversed.iter().map(Vec::as_slice)),
+            lengths: patterns.iter().map(Vec::len).collect(),
+        }
+    }
+
+    fn max_len(&self) -> usize {
+        self.lengths.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns all matches, including overlapping ones, ordered by their end.
+    pub fn find_iter<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Match> + 'a {
+        self.forward
+            .scan(haystack.bytes())
+            .map(|(i, pattern)| Match {
+                pattern,
+                start: i + 1 - self.lengths[pattern],
+                end: i + 1,
+            })
+    }
+
+    /// Returns all matches, including overlapping ones, ordered by their start from the back of the haystack.
+    pub fn rfind_iter<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let len = haystack.len();
+        self.backward
+            .scan(haystack.bytes().rev())
+            .map(move |(i, pattern)| Match {
+                pattern,
+                start: len - 1 - i,
+                end: len - 1 - i + self.lengths[pattern],
+            })
+    }
+
+    /// Returns the match that starts first. Of several matches with the same start, the longest one is returned.
+    /// Scanning stops as soon as no later match can start earlier.
+    #[must_use]
+    pub fn first(&self, haystack: &str) -> Option<Match> {
+        let max_len = self.max_len();
+        let mut best: Option<Match> = None;
+
+        for m in self.find_iter(haystack) {
+            if best.is_some_and(|b| m.end > b.start + max_len) {
+                break;
+            }
+            if best.is_none_or(|b| (m.start, b.len()) < (b.start, m.len())) {
+                best = Some(m);
+            }
+        }
+
+        best
+    }
+
+    /// Returns the match that ends last. Of several matches with the same end, the longest one is returned.
+    /// Scanning stops as soon as no earlier match can end later.
+    #[must_use]
+    pub fn last(&self, haystack: &str) -> Option<Match> {
+        let max_len = self.max_len();
+        let mut best: Option<Match> = None;
+
+        for m in self.rfind_iter(haystack) {
+            if best.is_some_and(|b| m.start + max_len < b.end) {
+                break;
+            }
+            if best.is_none_or(|b| (b.end, b.len()) < (m.end, m.len())) {
+                best = Some(m);
+            }
+        }
+
+        best
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Match, Matcher};
+
+    fn found(matches: impl Iterator<Item = Match>) -> Vec<(usize, usize, usize)> {
+        matches.map(|m| (m.pattern, m.start, m.end)).collect()
+    }
+
+    #[test]
+    fn finds_overlapping_matches() {
+        let matcher = Matcher::new(["he", "she", "his", "hers"]);
+        assert_eq!(
+            found(matcher.find_iter("ushers")),
+            vec![(1, 1, 4), (0, 2, 4), (3, 2, 6)]
+        );
+        assert_eq!(
+            found(matcher.rfind_iter("ushers")),
+            vec![(3, 2, 6), (0, 2, 4), (1, 1, 4)]
+        );
+    }
+
+    #[test]
+    fn finds_first_and_last() {
+        let matcher = Matcher::new(["one", "eight", "two", "1"]);
+        let first = matcher.first("xoneightwo1x").unwrap();
+        let last = matcher.last("xoneightwo1x").unwrap();
+        assert_eq!((first.pattern, first.start), (0, 1));
+        assert_eq!((last.pattern, last.start), (3, 10));
+
+        let last = matcher.last("xoneightwo").unwrap();
+        assert_eq!((last.pattern, last.start), (2, 7));
+        assert_eq!(matcher.first("nothing"), None);
+    }
+
+    #[test]
+    fn prefers_longest_on_ties() {
+        let matcher = Matcher::new(["b", "abc", "ab", "c"]);
+        assert_eq!(matcher.first("xabc").unwrap().pattern, 1);
+        assert_eq!(matcher.last("abcx").unwrap().pattern, 1);
+    }
+
+    #[test]
+    fn uses_byte_offsets_for_utf8() {
+        let matcher = Matcher::new(["é", "two", ""]);
+        let haystack = "ñtwoé";
+        let first = matcher.first(haystack).unwrap();
+        let last = matcher.last(haystack).unwrap();
+        assert_eq!(&haystack[first.start..first.end], "two");
+        assert_eq!(&haystack[last.start..last.end], "é");
+    }
+}
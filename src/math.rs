@@ -0,0 +1,201 @@
+/// Number theory and polynomial helpers that come up in many puzzles.
+/// All functions use exact integer arithmetic, so results are correct for the full 64-bit range.
+use std::ops::RangeInclusive;
+
+/// Returns the largest integer `r` with `r * r <= n`.
+#[must_use]
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // newton's method converges from above, so start at a power of two that is never below the root.
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[must_use]
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple. Panics on overflow in debug builds.
+#[must_use]
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    a / gcd(a, b) * b
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)`.
+#[must_use]
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+/// Computes `a * b mod m` without overflowing.
+#[must_use]
+pub fn mod_mul(a: u64, b: u64, m: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(m)) as u64
+}
+
+/// Computes `base ^ exp mod m` by repeated squaring.
+#[must_use]
+pub fn mod_pow(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, m);
+        }
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Returns `x` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+#[must_use]
+pub fn mod_inv(a: u64, m: u64) -> Option<u64> {
+    let (g, x, _) = extended_gcd(i128::from(a), i128::from(m));
+    (g == 1).then(|| x.rem_euclid(i128::from(m)) as u64)
+}
+
+/// Solves a system of congruences `x ≡ r (mod m)` given as `(r, m)` pairs.
+/// Moduli do not have to be coprime. Returns `(x, lcm of all moduli)` with the smallest non-negative `x`,
+/// or `None` if the system has no solution or the combined modulus does not fit into `u64`.
+#[must_use]
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    congruences
+        .iter()
+        .try_fold((0u64, 1u64), |(r1, m1), &(r2, m2)| {
+            let (r1, m1, r2, m2) = (
+                i128::from(r1),
+                i128::from(m1),
+                i128::from(r2) % i128::from(m2),
+                i128::from(m2),
+            );
+            let (g, inverse, _) = extended_gcd(m1, m2);
+            if (r2 - r1) % g != 0 {
+                return None;
+            }
+
+            let modulus = u64::try_from(m1 / g * m2).ok()?;
+            let step = (m2 / g) as u64;
+            let k = mod_mul(
+                ((r2 - r1) / g).rem_euclid(m2 / g) as u64,
+                inverse.rem_euclid(m2 / g) as u64,
+                step,
+            );
+            let x = (r1 + m1 * i128::from(k)).rem_euclid(i128::from(modulus));
+            Some((x as u64, modulus))
+        })
+}
+
+/// Evaluates a polynomial at `x`. Coefficients are ordered from the highest degree to the constant term.
+#[must_use]
+pub fn eval(coefficients: &[i128], x: i128) -> i128 {
+    coefficients.iter().fold(0, |acc, c| acc * x + c)
+}
+
+/// Returns the integers `x` for which `a * x² + b * x + c < 0`, or `None` if there are none.
+/// `a` has to be positive and the coefficients small enough for `b² - 4ac` to fit into `i128`.
+#[must_use]
+pub fn quadratic_below(a: i128, b: i128, c: i128) -> Option<RangeInclusive<i128>> {
+    assert!(a > 0, "the parabola has to open upwards");
+
+    let discriminant = b * b - 4 * a * c;
+    if discriminant <= 0 {
+        return None;
+    }
+
+    // the roots are (-b ± √d) / 2a. start next to them and step to the exact boundaries.
+    let root = isqrt(discriminant as u128) as i128;
+    let f = |x: i128| eval(&[a, b, c], x);
+    let mut low = (-b - root).div_euclid(2 * a);
+    let mut high = (-b + root).div_euclid(2 * a) + 1;
+
+    while f(low) >= 0 && low <= high {
+        low += 1;
+    }
+    while f(low - 1) < 0 {
+        low -= 1;
+    }
+    while f(high) >= 0 && high >= low {
+        high -= 1;
+    }
+    while f(high + 1) < 0 {
+        high += 1;
+    }
+
+    (low <= high).then_some(low..=high)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{crt, eval, gcd, isqrt, lcm, mod_inv, mod_pow, quadratic_below};
+
+    #[test]
+    fn isqrt_is_exact() {
+        for n in 0..10_000u128 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "isqrt({n}) = {r}");
+        }
+
+        let max = u128::from(u64::MAX);
+        assert_eq!(isqrt(max * max), max);
+        assert_eq!(isqrt(max * max - 1), max - 1);
+        assert_eq!(isqrt(u128::MAX), max);
+    }
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(mod_pow(3, 200, 13), 9);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(4, 8), None);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn quadratic() {
+        assert_eq!(eval(&[1, -7, 9], 2), -1);
+        // t * (7 - t) > 9
+        assert_eq!(quadratic_below(1, -7, 9), Some(2..=5));
+        // touching zero at the boundaries does not count.
+        assert_eq!(quadratic_below(1, -30, 200), Some(11..=19));
+        assert_eq!(quadratic_below(1, 0, -4), Some(-1..=1));
+        assert_eq!(quadratic_below(1, 0, 0), None);
+        assert_eq!(quadratic_below(4, -2, 0), None);
+    }
+}
@@ -1,9 +1,12 @@
 /// Wrapper module around the "aoc-cli" command-line.
 use std::{
     fmt::Display,
+    fs,
     process::{Command, Output, Stdio},
 };
 
+use crate::template::cache::{Cache, Resource};
+use crate::template::{config, secrets};
 use crate::Day;
 
 #[derive(Debug)]
@@ -48,30 +51,61 @@ pub fn read(day: Day) -> Result<Output, AocCommandError> {
         day,
     );
 
+    throttle()?;
     call_aoc_cli(&args)
 }
 
-pub fn download(day: Day) -> Result<Output, AocCommandError> {
-    let input_path = get_input_path(day);
-    let puzzle_path = get_puzzle_path(day);
+/// Downloads input and puzzle description, unless a fresh copy is cached. See [`crate::template::cache`].
+pub fn download(day: Day) -> Result<(), AocCommandError> {
+    let cache = Cache::default();
+    let year = get_year();
+
+    for (resource, folder, file) in [
+        (Resource::Input, "inputs", format!("{day}.txt")),
+        (Resource::Puzzle, "puzzles", format!("{day}.md")),
+    ] {
+        let target = secrets::path(folder, &file);
+        let cached = cache.path(year, day, resource);
+
+        if cache.is_fresh(year, day, resource) {
+            println!("🎄 Using cached {resource} for day {day}.");
+        } else {
+            cache
+                .prepare(year, day)
+                .map_err(|_| AocCommandError::IoError)?;
+            let (only, file) = match resource {
+                Resource::Input => ("--input-only", "--input-file"),
+                Resource::Puzzle => ("--puzzle-only", "--puzzle-file"),
+            };
+            let args = build_args(
+                "download",
+                &[
+                    "--overwrite".into(),
+                    only.into(),
+                    file.into(),
+                    cached.to_string_lossy().to_string(),
+                ],
+                day,
+            );
+            throttle()?;
+            call_aoc_cli(&args)?;
+            cache
+                .refreshed(year, day, resource)
+                .map_err(|_| AocCommandError::IoError)?;
+        }
 
-    let args = build_args(
-        "download",
-        &[
-            "--overwrite".into(),
-            "--input-file".into(),
-            input_path.to_string(),
-            "--puzzle-file".into(),
-            puzzle_path.to_string(),
-        ],
-        day,
-    );
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|_| AocCommandError::IoError)?;
+        }
+        fs::copy(&cached, &target).map_err(|_| AocCommandError::IoError)?;
+        secrets::record(folder, &file).map_err(|_| AocCommandError::IoError)?;
+        println!(
+            "🎄 Successfully wrote {resource} to \"{}\".",
+            target.display()
+        );
+    }
 
-    let output = call_aoc_cli(&args)?;
-    println!("---");
-    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
-    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
-    Ok(output)
+    Ok(())
 }
 
 pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandError> {
@@ -79,22 +113,39 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
     let mut args = build_args("submit", &[], day);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+    throttle()?;
+    let output = call_aoc_cli_with(&args, Stdio::piped())?;
+    let response = String::from_utf8_lossy(&output.stdout);
+    print!("{response}");
+
+    // the description of part two is only sent once part one is solved.
+    if part == 1 && is_correct(&response) {
+        Cache::default()
+            .mark_stale(get_year(), day)
+            .map_err(|_| AocCommandError::IoError)?;
+    }
+
+    Ok(output)
 }
 
-fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
+/// Path of the input of `day`, which lives in the store if one is configured. See [`crate::template::secrets`].
+#[must_use]
+pub fn get_input_path(day: Day) -> String {
+    secrets::path("inputs", &format!("{day}.txt"))
+        .to_string_lossy()
+        .to_string()
 }
 
-fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
+#[must_use]
+pub fn get_puzzle_path(day: Day) -> String {
+    secrets::path("puzzles", &format!("{day}.md"))
+        .to_string_lossy()
+        .to_string()
 }
 
-fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
-    }
+#[must_use]
+pub fn get_year() -> Option<u16> {
+    config::get().year
 }
 
 fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
@@ -105,16 +156,36 @@ fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
         cmd_args.push(year.to_string());
     }
 
+    if let Some(session_file) = &config::get().session_file {
+        cmd_args.push("--session-file".into());
+        cmd_args.push(session_file.to_string_lossy().to_string());
+    }
+
     cmd_args.append(&mut vec!["--day".into(), day.to_string(), command.into()]);
 
     cmd_args
 }
 
+fn throttle() -> Result<(), AocCommandError> {
+    Cache::default()
+        .throttle()
+        .map_err(|_| AocCommandError::IoError)
+}
+
+/// Whether the response to a submission says that the answer was correct.
+fn is_correct(response: &str) -> bool {
+    response.contains("That's the right answer")
+}
+
 fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
+    call_aoc_cli_with(args, Stdio::inherit())
+}
+
+fn call_aoc_cli_with(args: &[String], stdout: Stdio) -> Result<Output, AocCommandError> {
     // println!("Calling >aoc with: {}", args.join(" "));
     let output = Command::new("aoc")
         .args(args)
-        .stdout(Stdio::inherit())
+        .stdout(stdout)
         .stderr(Stdio::inherit())
         .output()
         .map_err(|_| AocCommandError::CommandNotCallable)?;
@@ -125,3 +196,21 @@ fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
         Err(AocCommandError::BadExitStatus(output))
     }
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::is_correct;
+
+    #[test]
+    fn detects_correct_answers() {
+        assert!(is_correct(
+            "That's the right answer! You are one gold star closer to finding the chief."
+        ));
+        assert!(!is_correct(
+            "That's not the right answer; your answer is too low."
+        ));
+        assert!(!is_correct(
+            "You gave an answer too recently; you have 42s left to wait."
+        ));
+    }
+}
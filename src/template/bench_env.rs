@@ -0,0 +1,255 @@
+/// Keeps benchmarks comparable between runs: describes the machine timings were taken on, warns about conditions that
+/// make them unreliable, pins benched processes to a CPU core and fixes the environment variables that affect timings.
+/// System information is read from `/proc` and `/sys`, so most of it is only available on Linux.
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    process::{self, Command, Stdio},
+    sync::Once,
+    thread,
+};
+
+/// A load average above this means other programs compete with the benchmark for CPU time.
+const MAX_LOAD: f64 = 1.0;
+
+/// Environment variables that replace or tune the allocator, removed for solutions benched by `cargo time`.
+/// All other variables are passed on.
+const REMOVED_VARS: [&str; 6] = [
+    "LD_PRELOAD",
+    "GLIBC_TUNABLES",
+    "MALLOC_ARENA_MAX",
+    "MALLOC_CHECK_",
+    "MALLOC_PERTURB_",
+    "MALLOC_CONF",
+];
+/// Set for every benched solution, as capturing a backtrace makes panics and errors slow.
+const FIXED_VARS: [(&str, &str); 2] = [("RUST_BACKTRACE", "0"), ("RUST_LIB_BACKTRACE", "0")];
+
+/// The machine and build timings are taken with. Fields are `None` if they could not be determined.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Environment {
+    pub cpu_model: Option<String>,
+    pub cpus: Option<usize>,
+    /// Frequency scaling governors of all cores, e.g. `performance` or `powersave`.
+    pub governors: BTreeSet<String>,
+    /// Load average of the last minute.
+    pub load: Option<f64>,
+    pub rustc: Option<String>,
+    pub opt_level: String,
+    pub pinned_cpu: Option<usize>,
+}
+
+impl Environment {
+    /// Inspects the current machine. `release` selects the cargo profile the opt level is read for.
+    #[must_use]
+    pub fn detect(release: bool, pinned_cpu: Option<usize>) -> Self {
+        let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+
+        Self {
+            cpu_model: fs::read_to_string("/proc/cpuinfo")
+                .ok()
+                .and_then(|cpuinfo| parse_cpu_model(&cpuinfo)),
+            cpus: thread::available_parallelism().ok().map(usize::from),
+            governors: read_governors(),
+            load: fs::read_to_string("/proc/loadavg")
+                .ok()
+                .and_then(|loadavg| parse_load(&loadavg)),
+            rustc: Command::new(rustc)
+                .arg("--version")
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            opt_level: opt_level(release),
+            pinned_cpu,
+        }
+    }
+
+    /// Conditions that make timings vary between runs.
+    #[must_use]
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = vec![];
+
+        let scaled: Vec<_> = self
+            .governors
+            .iter()
+            .filter(|g| *g != "performance")
+            .map(String::as_str)
+            .collect();
+        if !scaled.is_empty() {
+            warnings.push(format!(
+                "CPU frequency scaling governor is `{}`, timings may vary. Try `sudo cpupower frequency-set -g performance`.",
+                scaled.join("`, `")
+            ));
+        }
+
+        if let Some(load) = self.load.filter(|&load| load > MAX_LOAD) {
+            warnings.push(format!(
+                "load average is {load:.2}, close other programs for stable timings."
+            ));
+        }
+
+        if let (Some(cpu), Some(cpus)) = (self.pinned_cpu, self.cpus) {
+            if cpu >= cpus {
+                warnings.push(format!(
+                    "cannot pin to CPU {cpu}, this machine has {cpus} CPUs (0-{}).",
+                    cpus - 1
+                ));
+            }
+        }
+
+        warnings
+    }
+
+    /// One line describing the environment, stored next to the timings in the readme.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut parts = vec![];
+
+        match (&self.cpu_model, self.cpus) {
+            (Some(model), Some(cpus)) => parts.push(format!("{model} ({cpus} threads)")),
+            (Some(model), None) => parts.push(model.clone()),
+            (None, Some(cpus)) => parts.push(format!("{cpus} threads")),
+            (None, None) => {}
+        }
+        parts.extend(self.rustc.clone());
+        parts.push(format!("opt-level {}", self.opt_level));
+        if let Some(cpu) = self.pinned_cpu {
+            parts.push(format!("pinned to CPU {cpu}"));
+        }
+
+        parts.join(", ")
+    }
+}
+
+fn parse_cpu_model(cpuinfo: &str) -> Option<String> {
+    cpuinfo
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim() == "model name")
+        .map(|(_, model)| model.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn parse_load(loadavg: &str) -> Option<f64> {
+    loadavg.split_whitespace().next()?.parse().ok()
+}
+
+fn read_governors() -> BTreeSet<String> {
+    let Ok(entries) = fs::read_dir("/sys/devices/system/cpu") else {
+        return BTreeSet::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("cpu")
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|entry| fs::read_to_string(entry.path().join("cpufreq/scaling_governor")).ok())
+        .map(|governor| governor.trim().to_string())
+        .collect()
+}
+
+/// The opt level of the cargo profile, taking the environment overrides cargo supports into account.
+fn opt_level(release: bool) -> String {
+    let (profile, default) = if release {
+        ("RELEASE", "3")
+    } else {
+        ("DEV", "0")
+    };
+    env::var(format!("CARGO_PROFILE_{profile}_OPT_LEVEL")).unwrap_or_else(|_| default.into())
+}
+
+/// Removes [`REMOVED_VARS`] from the environment of `cmd` and sets [`FIXED_VARS`].
+pub fn fix_env(cmd: &mut Command) {
+    for name in REMOVED_VARS {
+        cmd.env_remove(name);
+    }
+    cmd.envs(FIXED_VARS);
+}
+
+static PIN: Once = Once::new();
+
+/// Pins the current process to `cpu` with `taskset`, so the scheduler does not move it between cores while it is
+/// benched. Only the first call does anything, failures are printed as warnings.
+pub fn pin_once(cpu: usize) {
+    PIN.call_once(|| {
+        let status = Command::new("taskset")
+            .args(["--cpu-list", "--pid", &cpu.to_string()])
+            .arg(process::id().to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+
+        if !status.is_ok_and(|s| s.success()) {
+            eprintln!("Warning: could not pin the benchmark to CPU {cpu}. Is \"taskset\" (util-linux) installed?");
+        }
+    });
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{collections::BTreeSet, ffi::OsStr, process::Command};
+
+    use super::{fix_env, parse_cpu_model, parse_load, Environment};
+
+    #[test]
+    fn parses_proc_files() {
+        let cpuinfo = "processor\t: 0\nvendor_id\t: AuthenticAMD\nmodel name\t: AMD Ryzen 7  5800X 8-Core Processor\n";
+        assert_eq!(
+            parse_cpu_model(cpuinfo),
+            Some("AMD Ryzen 7 5800X 8-Core Processor".into())
+        );
+        assert_eq!(parse_cpu_model("processor\t: 0\n"), None);
+        assert_eq!(parse_load("0.52 0.58 0.59 1/467 12345\n"), Some(0.52));
+    }
+
+    #[test]
+    fn describes_environment() {
+        let environment = Environment {
+            cpu_model: Some("Ryzen".into()),
+            cpus: Some(4),
+            governors: BTreeSet::from(["performance".into(), "powersave".into()]),
+            load: Some(2.5),
+            rustc: Some("rustc 1.74.0".into()),
+            opt_level: "3".into(),
+            pinned_cpu: Some(4),
+        };
+
+        assert_eq!(
+            environment.summary(),
+            "Ryzen (4 threads), rustc 1.74.0, opt-level 3, pinned to CPU 4"
+        );
+
+        let warnings = environment.warnings();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("`powersave`"));
+        assert!(warnings[1].contains("2.50"));
+        assert!(warnings[2].contains("4 CPUs (0-3)"));
+
+        assert_eq!(
+            Environment {
+                governors: BTreeSet::from(["performance".into()]),
+                load: Some(0.3),
+                ..environment
+            }
+            .warnings()
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn fixes_timing_variables() {
+        let mut cmd = Command::new("cargo");
+        fix_env(&mut cmd);
+        let envs: Vec<_> = cmd.get_envs().collect();
+
+        assert!(envs.contains(&(OsStr::new("LD_PRELOAD"), None)));
+        assert!(envs.contains(&(OsStr::new("RUST_BACKTRACE"), Some(OsStr::new("0")))));
+        // everything else, e.g. `TERM` or `TMPDIR`, is inherited.
+        assert!(!envs.iter().any(|(name, _)| *name == "TERM"));
+    }
+}
@@ -0,0 +1,213 @@
+/// Local cache for everything fetched from Advent of Code, and a throttle for requests to the site.
+/// Inputs never change once released, so they are fetched exactly once. A puzzle description fetched before part
+/// one was solved lacks part two, so it is refreshed once part one has been solved.
+use std::{
+    fmt::Display,
+    fs, io,
+    path::PathBuf,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::template::commands::read::PART_TWO_HEADING;
+use crate::template::config;
+use crate::Day;
+
+const LAST_REQUEST_FILE: &str = "last_request";
+const STALE_MARKER: &str = "puzzle.stale";
+
+/// Minimum time between two requests to the site.
+pub const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Input,
+    Puzzle,
+}
+
+impl Resource {
+    fn file_name(self) -> &'static str {
+        match self {
+            Resource::Input => "input.txt",
+            Resource::Puzzle => "puzzle.md",
+        }
+    }
+}
+
+impl Display for Resource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Resource::Input => write!(f, "input"),
+            Resource::Puzzle => write!(f, "puzzle"),
+        }
+    }
+}
+
+/// Cached responses, keyed by year and day. Without a configured year, aoc-cli uses the latest event, which is
+/// cached as `latest`.
+pub struct Cache {
+    root: PathBuf,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::at(config::get().paths.data.join("cache"))
+    }
+}
+
+impl Cache {
+    pub fn at(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn dir(&self, year: Option<u16>, day: Day) -> PathBuf {
+        let year = year.map_or("latest".to_string(), |year| year.to_string());
+        self.root.join(year).join(day.to_string())
+    }
+
+    #[must_use]
+    pub fn path(&self, year: Option<u16>, day: Day, resource: Resource) -> PathBuf {
+        self.dir(year, day).join(resource.file_name())
+    }
+
+    /// Whether the cached copy can be used instead of fetching the resource again.
+    #[must_use]
+    pub fn is_fresh(&self, year: Option<u16>, day: Day, resource: Resource) -> bool {
+        let Ok(content) = fs::read_to_string(self.path(year, day, resource)) else {
+            return false;
+        };
+        match resource {
+            Resource::Input => true,
+            Resource::Puzzle => {
+                content.contains(PART_TWO_HEADING)
+                    || !self.dir(year, day).join(STALE_MARKER).exists()
+            }
+        }
+    }
+
+    /// Creates the directory for a resource that is about to be fetched.
+    pub fn prepare(&self, year: Option<u16>, day: Day) -> io::Result<()> {
+        fs::create_dir_all(self.dir(year, day))
+    }
+
+    /// Records that a resource was fetched again.
+    pub fn refreshed(&self, year: Option<u16>, day: Day, resource: Resource) -> io::Result<()> {
+        match resource {
+            Resource::Input => Ok(()),
+            Resource::Puzzle => match fs::remove_file(self.dir(year, day).join(STALE_MARKER)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Records that part one was solved, so a cached description without part two has to be fetched again.
+    pub fn mark_stale(&self, year: Option<u16>, day: Day) -> io::Result<()> {
+        self.prepare(year, day)?;
+        fs::write(self.dir(year, day).join(STALE_MARKER), "")
+    }
+
+    /// Waits until [`MIN_REQUEST_INTERVAL`] has passed since the last request and records a new one.
+    /// The time of the last request is persisted, so the throttle also holds across separate commands.
+    pub fn throttle(&self) -> io::Result<()> {
+        let path = self.root.join(LAST_REQUEST_FILE);
+        let last = fs::read_to_string(&path)
+            .ok()
+            .and_then(|millis| millis.trim().parse().ok());
+
+        let delay = throttle_delay(last, now_millis());
+        if !delay.is_zero() {
+            println!(
+                "⏳ Waiting {:.1}s before the next request to Advent of Code...",
+                delay.as_secs_f64()
+            );
+            thread::sleep(delay);
+        }
+
+        fs::create_dir_all(&self.root)?;
+        fs::write(path, now_millis().to_string())
+    }
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis())
+}
+
+/// Time left to wait at `now` if the last request happened at `last`, both in milliseconds since the unix epoch.
+fn throttle_delay(last: Option<u128>, now: u128) -> Duration {
+    let Some(last) = last else {
+        return Duration::ZERO;
+    };
+    let elapsed = Duration::from_millis(now.saturating_sub(last) as u64);
+    MIN_REQUEST_INTERVAL.saturating_sub(elapsed)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use super::{throttle_delay, Cache, Resource, MIN_REQUEST_INTERVAL};
+    use crate::day;
+
+    fn temp_cache(name: &str) -> Cache {
+        let root = env::temp_dir().join(format!("aoc-cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        Cache::at(root)
+    }
+
+    #[test]
+    fn keys_by_year_and_day() {
+        let cache = Cache::at("cache");
+        assert_eq!(
+            cache.path(Some(2023), day!(5), Resource::Input),
+            std::path::Path::new("cache/2023/05/input.txt")
+        );
+        assert_eq!(
+            cache.path(None, day!(5), Resource::Puzzle),
+            std::path::Path::new("cache/latest/05/puzzle.md")
+        );
+    }
+
+    #[test]
+    fn refreshes_puzzle_after_part_one() {
+        let cache = temp_cache("fresh");
+        let (year, day) = (Some(2023), day!(1));
+        assert!(!cache.is_fresh(year, day, Resource::Input));
+
+        cache.prepare(year, day).unwrap();
+        fs::write(cache.path(year, day, Resource::Input), "1abc2").unwrap();
+        fs::write(cache.path(year, day, Resource::Puzzle), "## --- Day 1 ---").unwrap();
+        assert!(cache.is_fresh(year, day, Resource::Input));
+        assert!(cache.is_fresh(year, day, Resource::Puzzle));
+
+        cache.mark_stale(year, day).unwrap();
+        assert!(cache.is_fresh(year, day, Resource::Input));
+        assert!(!cache.is_fresh(year, day, Resource::Puzzle));
+
+        cache.refreshed(year, day, Resource::Puzzle).unwrap();
+        assert!(cache.is_fresh(year, day, Resource::Puzzle));
+
+        // a description that already holds part two never goes stale.
+        fs::write(
+            cache.path(year, day, Resource::Puzzle),
+            "## --- Day 1 ---\n## --- Part Two ---",
+        )
+        .unwrap();
+        cache.mark_stale(year, day).unwrap();
+        assert!(cache.is_fresh(year, day, Resource::Puzzle));
+    }
+
+    #[test]
+    fn computes_throttle_delay() {
+        assert_eq!(throttle_delay(None, 10_000), Duration::ZERO);
+        assert_eq!(
+            throttle_delay(Some(10_000), 12_000),
+            MIN_REQUEST_INTERVAL - Duration::from_secs(2)
+        );
+        assert_eq!(throttle_delay(Some(10_000), 20_000), Duration::ZERO);
+        // a clock that went backwards waits the full interval.
+        assert_eq!(throttle_delay(Some(20_000), 10_000), MIN_REQUEST_INTERVAL);
+    }
+}
@@ -1,6 +1,11 @@
-use std::io;
+use std::{
+    io,
+    process::{self, ExitStatus},
+};
 
 use crate::template::{
+    bench_env::Environment,
+    config,
     readme_benchmarks::{self, Timings},
     ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
 };
@@ -8,6 +13,16 @@ use crate::{all_days, Day};
 
 pub fn handle(is_release: bool, is_timed: bool) {
     let mut timings: Vec<Timings> = vec![];
+    let mut failed = vec![];
+
+    let environment = Environment::detect(is_release, config::get().bench.pin_cpu);
+    if is_timed {
+        println!("{ANSI_ITALIC}{}{ANSI_RESET}", environment.summary());
+        for warning in environment.warnings() {
+            eprintln!("Warning: {warning}");
+        }
+        println!();
+    }
 
     all_days().for_each(|day| {
         if day > 1 {
@@ -17,13 +32,17 @@ pub fn handle(is_release: bool, is_timed: bool) {
         println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
         println!("------");
 
-        let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
-
-        if output.is_empty() {
-            println!("Not solved.");
-        } else {
-            let val = child_commands::parse_exec_time(&output, day);
-            timings.push(val);
+        match child_commands::run_solution(day, is_timed, is_release) {
+            Ok(reports) if reports.is_empty() => println!("Not solved."),
+            Ok(reports) => timings.push(child_commands::timings(&reports, day, &environment)),
+            Err(Error::Failed(status)) => {
+                println!("Failed, the solution exited with {status}.");
+                failed.push(day);
+            }
+            Err(e) => {
+                eprintln!("Failed to run solution: {e:?}");
+                failed.push(day);
+            }
         }
     });
 
@@ -32,7 +51,7 @@ pub fn handle(is_release: bool, is_timed: bool) {
 
         println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
 
-        if is_release {
+        if is_release && config::get().bench.update_readme && failed.is_empty() {
             match readme_benchmarks::update(timings, total_millis) {
                 Ok(()) => println!("Successfully updated README with benchmarks."),
                 Err(_) => {
@@ -41,11 +60,22 @@ pub fn handle(is_release: bool, is_timed: bool) {
             }
         }
     }
+
+    if !failed.is_empty() {
+        let days: Vec<_> = failed.iter().map(Day::to_string).collect();
+        eprintln!("\nFailed days: {}", days.join(", "));
+        if is_timed && is_release && config::get().bench.update_readme {
+            eprintln!("The readme was not updated with benchmarks.");
+        }
+        process::exit(1);
+    }
 }
 
 #[derive(Debug)]
 pub enum Error {
     BrokenPipe,
+    /// The solution exited with an error, e.g. because a part panicked.
+    Failed(ExitStatus),
     Parser(String),
     IO(io::Error),
 }
@@ -58,13 +88,17 @@ impl From<std::io::Error> for Error {
 
 #[must_use]
 pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+    format!("./{}", config::get().bin_path(day).display())
 }
 
 /// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
-mod child_commands {
+/// This module encapsulates interaction with these binaries, both invoking them as well as collecting their reports.
+pub(crate) mod child_commands {
     use super::{get_path_for_bin, Error};
+    use crate::template::{
+        bench_env::{self, Environment},
+        runner::RunReport,
+    };
     use crate::Day;
     use std::{
         io::{BufRead, BufReader},
@@ -73,8 +107,13 @@ mod child_commands {
         thread,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day and return the reports of its parts.
+    /// Timed runs fix the environment variables that affect timings, see [`bench_env::fix_env`].
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+    ) -> Result<Vec<RunReport>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
@@ -87,25 +126,32 @@ mod child_commands {
             args.push("--release");
         }
 
+        args.extend(["--", "--format", "json"]);
+
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
             args.push("--time");
         }
 
         // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+        // forward output to stdout/stderr while collecting the reports printed to stdout.
 
-        let mut cmd = Command::new("cargo")
-            .args(&args)
+        let mut cmd = Command::new("cargo");
+        cmd.args(&args)
+            .stdin(Stdio::null())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+            .stderr(Stdio::piped());
+
+        if is_timed {
+            bench_env::fix_env(&mut cmd);
+        }
+
+        let mut cmd = cmd.spawn()?;
 
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-        let mut output = vec![];
+        let mut reports = vec![];
 
         let thread = thread::spawn(move || {
             stderr.lines().for_each(|line| {
@@ -115,77 +161,53 @@ mod child_commands {
 
         for line in stdout.lines() {
             let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
+            // anything else is output of the solution itself, e.g. debug prints.
+            match RunReport::from_json(&line) {
+                Some(report) => {
+                    println!("{}", report.to_human());
+                    reports.push(report);
+                }
+                None => println!("{line}"),
+            }
         }
 
         thread.join().unwrap();
-        cmd.wait()?;
+        let status = cmd.wait()?;
+        if !status.success() {
+            return Err(Error::Failed(status));
+        }
 
-        Ok(output)
+        Ok(reports)
     }
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timings {
+    /// Collects the timings of benched parts for the readme, together with the environment they were taken in.
+    pub fn timings(reports: &[RunReport], day: Day, environment: &Environment) -> super::Timings {
         let mut timings = super::Timings {
             day,
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            environment: environment.summary(),
         };
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
+        for report in reports {
+            let Some(stats) = report.stats.filter(|s| s.is_benched()) else {
+                continue;
+            };
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
-
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
-                }
+            let timing = Some(format!("{:.1?}", stats.mean));
+            match report.part {
+                1 => timings.part_1 = timing,
+                2 => timings.part_2 = timing,
+                _ => continue,
+            }
 
-                timings.total_nanos += nanos;
-            });
+            timings.total_nanos += stats.mean.as_nanos() as f64;
+        }
 
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
-    }
-
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
-
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
-    }
-
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
     #[cfg(feature = "test_lib")]
     macro_rules! assert_approx_eq {
@@ -202,49 +224,69 @@ mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use std::time::Duration;
 
+        use super::timings;
         use crate::day;
+        use crate::template::bench_env::Environment;
+        use crate::template::runner::{RunReport, Stats, Status};
+
+        fn report(part: u8, answer: Option<&str>, nanos: u64, samples: u128) -> RunReport {
+            let mean = Duration::from_nanos(nanos);
+            RunReport {
+                day: day!(1),
+                part,
+                status: if answer.is_some() {
+                    Status::Solved
+                } else {
+                    Status::Unsolved
+                },
+                answer: answer.map(String::from),
+                stats: Some(Stats {
+                    samples,
+                    mean,
+                    min: mean,
+                    max: mean,
+                }),
+            }
+        }
 
         #[test]
         fn test_well_formed() {
-            let res = parse_exec_time(
+            let res = timings(
                 &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
+                    report(1, Some("0"), 74, 100_000),
+                    report(2, Some("10"), 74_130_000, 99_999),
                 ],
                 day!(1),
+                &Environment::default(),
             );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_approx_eq!(res.total_nanos, 74130074_f64);
+            assert_eq!(res.part_1.unwrap(), "74.0ns");
+            assert_eq!(res.part_2.unwrap(), "74.1ms");
         }
 
         #[test]
-        fn test_patterns_in_input() {
-            let res = parse_exec_time(
+        fn test_single_runs() {
+            let res = timings(
                 &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
+                    report(1, Some("(2s @ 5 samples)"), 2_000_000_000, 5),
+                    report(2, Some("10s"), 100_000_000, 1),
                 ],
                 day!(1),
+                &Environment::default(),
             );
-            assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_approx_eq!(res.total_nanos, 2000000000_f64);
+            assert_eq!(res.part_1.unwrap(), "2.0s");
+            assert_eq!(res.part_2.is_none(), true);
         }
 
         #[test]
         fn test_missing_parts() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
-                ],
+            let res = timings(
+                &[RunReport::skipped(day!(1), 1)],
                 day!(1),
+                &Environment::default(),
             );
             assert_approx_eq!(res.total_nanos, 0_f64);
             assert_eq!(res.part_1.is_none(), true);
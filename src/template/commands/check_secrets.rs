@@ -0,0 +1,136 @@
+/// Fails if puzzle inputs or descriptions are staged for commit. Meant to be run as a git pre-commit hook.
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+};
+
+use crate::template::secrets::{self, Manifest, SECRET_FOLDERS};
+use crate::template::{config, hash::fnv1a};
+
+pub fn handle() {
+    if let Some(store) = unignored_store() {
+        eprintln!(
+            "The store \"{}\" is inside the repository but not git-ignored. Add it to .gitignore so its files are never staged.",
+            store.display()
+        );
+        process::exit(1);
+    }
+
+    let staged = match git(&[
+        "diff",
+        "--cached",
+        "--name-only",
+        "--diff-filter=ACMR",
+        "-z",
+    ]) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("could not list staged files: {e}");
+            process::exit(1);
+        }
+    };
+
+    let dirs = SECRET_FOLDERS.map(|folder| config::get().data_dir(folder));
+    let known = known_hashes(&dirs);
+    let staged = String::from_utf8_lossy(&staged);
+    let offending: Vec<&str> = staged
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .filter(|path| is_secret_path(path, &dirs) || is_known_content(path, &known))
+        .collect();
+
+    if offending.is_empty() {
+        println!("🎄 No puzzle inputs are staged for commit.");
+        return;
+    }
+
+    eprintln!("Puzzle inputs or descriptions are staged for commit:");
+    for path in &offending {
+        eprintln!("  {path}");
+    }
+    eprintln!("Unstage them with \"git restore --staged <file>\". Advent of Code asks for them not to be published.");
+    process::exit(1);
+}
+
+fn git(args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// The configured store, if it is inside the work tree and not git-ignored. Only `.aoc-store` is ignored by default.
+fn unignored_store() -> Option<PathBuf> {
+    let store = secrets::store()?;
+    let ignored = SECRET_FOLDERS.iter().all(|folder| {
+        // exits with 1 for paths that are not ignored and fails for paths outside of the work tree.
+        Command::new("git")
+            .args(["check-ignore", "-q"])
+            .arg(store.join(folder))
+            .stderr(Stdio::null())
+            .status()
+            .map_or(true, |status| status.code() != Some(1))
+    });
+    (!ignored).then_some(store)
+}
+
+/// Files in the secret data folders, apart from the placeholders that keep the folders around.
+fn is_secret_path(path: &str, dirs: &[PathBuf]) -> bool {
+    dirs.iter().any(|dir| {
+        Path::new(path)
+            .strip_prefix(dir)
+            .is_ok_and(|file| file != Path::new(".keep"))
+    })
+}
+
+/// Hashes of all inputs and descriptions we know about, so copies in other folders are caught as well.
+fn known_hashes(dirs: &[PathBuf]) -> HashSet<u64> {
+    let mut known: HashSet<u64> = Manifest::load().hashes().collect();
+    let store = secrets::store();
+    let store_dirs = SECRET_FOLDERS
+        .iter()
+        .filter_map(|folder| Some(store.as_ref()?.join(folder)));
+
+    for dir in dirs.iter().cloned().chain(store_dirs) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        known.extend(
+            entries
+                .filter_map(|entry| fs::read(entry.ok()?.path()).ok())
+                .filter(|content| !content.is_empty())
+                .map(|content| fnv1a(&content)),
+        );
+    }
+    known
+}
+
+/// Whether the staged version of `path` is one of the known inputs. Empty files never count.
+fn is_known_content(path: &str, known: &HashSet<u64>) -> bool {
+    git(&["show", &format!(":{path}")])
+        .is_ok_and(|content| !content.is_empty() && known.contains(&fnv1a(&content)))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::is_secret_path;
+
+    #[test]
+    fn detects_secret_paths() {
+        let dirs = [PathBuf::from("data/inputs"), PathBuf::from("data/puzzles")];
+        assert!(is_secret_path("data/inputs/01.txt", &dirs));
+        assert!(is_secret_path("data/puzzles/01.md", &dirs));
+        assert!(!is_secret_path("data/inputs/.keep", &dirs));
+        assert!(!is_secret_path("data/examples/01.txt", &dirs));
+        assert!(!is_secret_path("src/bin/01.rs", &dirs));
+    }
+}
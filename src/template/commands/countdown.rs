@@ -0,0 +1,169 @@
+use std::{
+    io::{stdout, Write},
+    process, thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::{download, scaffold};
+use crate::template::config;
+use crate::Day;
+
+/// Puzzles unlock at midnight US Eastern time. December is always outside of daylight saving time, so that is
+/// 05:00 UTC.
+const UNLOCK_HOUR_UTC: u64 = 5;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Source of the current time, so the countdown can be tested without waiting.
+pub trait Clock {
+    /// Seconds since the unix epoch.
+    fn now(&self) -> u64;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Days between the unix epoch and the given date of the proleptic gregorian calendar.
+fn days_from_civil(year: i64, month: u64, day: u64) -> i64 {
+    // shift the year to start in march, so the leap day is the last day of the year.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400) as u64;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era as i64 - 719_468
+}
+
+/// The moment the puzzle of `day` is released, in seconds since the unix epoch.
+#[must_use]
+pub fn unlock_time(year: u16, day: Day) -> u64 {
+    let days = days_from_civil(i64::from(year), 12, u64::from(day.into_inner()));
+    days as u64 * SECONDS_PER_DAY + UNLOCK_HOUR_UTC * 60 * 60
+}
+
+/// Returns the next day that is not released yet at `now`, together with its unlock time.
+#[must_use]
+pub fn next_unlock(year: u16, now: u64) -> Option<(Day, u64)> {
+    crate::all_days()
+        .map(|day| (day, unlock_time(year, day)))
+        .find(|&(_, unlock)| unlock > now)
+}
+
+/// Formats a number of seconds as `[Nd ]HH:MM:SS`.
+fn format_remaining(seconds: u64) -> String {
+    let (days, rest) = (seconds / SECONDS_PER_DAY, seconds % SECONDS_PER_DAY);
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    );
+    if days > 0 {
+        format!("{days}d {time}")
+    } else {
+        time
+    }
+}
+
+/// Blocks until `unlock`, calling `tick` with the remaining seconds about once per second.
+pub fn wait_until(clock: &impl Clock, unlock: u64, mut tick: impl FnMut(u64)) {
+    loop {
+        let now = clock.now();
+        if now >= unlock {
+            return;
+        }
+        tick(unlock - now);
+        clock.sleep(Duration::from_secs(1));
+    }
+}
+
+pub fn handle() {
+    let Some(year) = config::get().year else {
+        eprintln!("No year is configured, set `year` in `aoc.toml`.");
+        process::exit(1);
+    };
+
+    let clock = SystemClock;
+    let Some((day, unlock)) = next_unlock(year, clock.now()) else {
+        println!("All puzzles of {year} have been released.");
+        return;
+    };
+
+    wait_until(&clock, unlock, |remaining| {
+        print!(
+            "\r🎄 Day {day} unlocks in {}   ",
+            format_remaining(remaining)
+        );
+        let _ = stdout().flush();
+    });
+    println!("\r🎄 Day {day} is unlocked!            ");
+
+    if config::get().bin_path(day).exists() {
+        println!("Module for day {day} already exists, skipping scaffold.");
+    } else {
+        scaffold::handle(day);
+    }
+    download::handle(day);
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{cell::Cell, time::Duration};
+
+    use super::{format_remaining, next_unlock, unlock_time, wait_until, Clock};
+    use crate::day;
+
+    struct FakeClock(Cell<u64>);
+
+    impl Clock for FakeClock {
+        fn now(&self) -> u64 {
+            self.0.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.0.set(self.0.get() + duration.as_secs());
+        }
+    }
+
+    #[test]
+    fn computes_unlock_time() {
+        // 2023-12-01T05:00:00Z
+        assert_eq!(unlock_time(2023, day!(1)), 1_701_406_800);
+        // 2024-12-25T05:00:00Z
+        assert_eq!(unlock_time(2024, day!(25)), 1_735_102_800);
+    }
+
+    #[test]
+    fn finds_next_unlock() {
+        let first = unlock_time(2023, day!(1));
+        assert_eq!(next_unlock(2023, 0), Some((day!(1), first)));
+        assert_eq!(next_unlock(2023, first), Some((day!(2), first + 86_400)));
+        assert_eq!(next_unlock(2023, unlock_time(2023, day!(25))), None);
+    }
+
+    #[test]
+    fn waits_until_unlock() {
+        let clock = FakeClock(Cell::new(100));
+        let mut ticks = vec![];
+        wait_until(&clock, 103, |remaining| ticks.push(remaining));
+        assert_eq!(ticks, vec![3, 2, 1]);
+        assert_eq!(clock.now(), 103);
+    }
+
+    #[test]
+    fn formats_remaining() {
+        assert_eq!(format_remaining(59), "00:00:59");
+        assert_eq!(format_remaining(90_061), "1d 01:01:01");
+    }
+}
@@ -0,0 +1,411 @@
+/// Interactive overview of the whole advent calendar.
+/// Renders every day's state in a 5x5 grid and allows running tests, solutions and benchmarks for the selected day.
+use std::{
+    fs,
+    io::{stdin, stdout, Read, Write},
+    path::Path,
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use super::all::{child_commands, get_path_for_bin};
+use crate::template::{aoc_cli, config, ANSI_BOLD, ANSI_CLEAR, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{bench_env::Environment, runner::RunReport};
+use crate::{all_days, Day};
+
+const COLUMNS: usize = 5;
+/// Terminals send the bytes of an escape sequence together, so an `ESC` that is not followed by more bytes within
+/// this time is a key press of its own.
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Default)]
+struct DayStatus {
+    scaffolded: bool,
+    has_input: bool,
+    has_examples: bool,
+    tests: Option<bool>,
+    answers: [Option<String>; 2],
+    verified: [Option<bool>; 2],
+    timings: [Option<String>; 2],
+}
+
+impl DayStatus {
+    fn load(day: Day, readme_timings: &[(Day, [Option<String>; 2])]) -> Self {
+        let answers = fs::read_to_string(aoc_cli::get_puzzle_path(day))
+            .map(|puzzle| parse_known_answers(&puzzle))
+            .unwrap_or_default();
+
+        let timings = readme_timings
+            .iter()
+            .find(|(d, _)| *d == day)
+            .map(|(_, timings)| timings.clone())
+            .unwrap_or_default();
+        let examples = &config::get().paths.examples;
+
+        Self {
+            scaffolded: fs::metadata(get_path_for_bin(day)).is_ok(),
+            has_input: is_non_empty(Path::new(&aoc_cli::get_input_path(day))),
+            has_examples: is_non_empty(&examples.join(format!("{day}.txt")))
+                || is_non_empty(&examples.join(format!("{day}-1.txt"))),
+            answers,
+            timings,
+            ..Self::default()
+        }
+    }
+
+    /// Compares the answers of a solution run against the answers accepted by the website.
+    fn verify(&mut self, reports: &[RunReport]) {
+        for report in reports {
+            let (Some(answer), Some(Some(known))) = (
+                &report.answer,
+                self.answers.get(usize::from(report.part) - 1),
+            ) else {
+                continue;
+            };
+            self.verified[usize::from(report.part) - 1] = Some(known == answer);
+        }
+    }
+}
+
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Escape,
+    Char(char),
+}
+
+/// Puts the controlling terminal into non-canonical mode so single key presses can be read.
+/// The previous settings are restored when the value is dropped.
+struct RawTerminal {
+    saved: Option<String>,
+}
+
+impl RawTerminal {
+    fn enter() -> Self {
+        let saved = stty(&["-g"]).filter(|_| stty(&["-icanon", "-echo", "min", "1"]).is_some());
+        Self { saved }
+    }
+
+    fn is_raw(&self) -> bool {
+        self.saved.is_some()
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        if let Some(saved) = &self.saved {
+            stty(&[saved.trim()]);
+        }
+    }
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn is_non_empty(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.len() > 0)
+}
+
+/// Forwards the bytes read from stdin, so they can be waited for with a timeout. Stops at the end of the input.
+/// Child commands get no stdin, so this is the only reader.
+fn spawn_stdin_reader() -> Receiver<u8> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for byte in stdin().lock().bytes() {
+            if byte.ok().and_then(|byte| tx.send(byte).ok()).is_none() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Drops the keys that were pressed while a child command ran.
+fn discard_keys(input: &Receiver<u8>) {
+    while input.recv_timeout(ESCAPE_TIMEOUT).is_ok() {}
+}
+
+/// Waits for the next key press. `None` once stdin is closed.
+fn read_key(input: &Receiver<u8>) -> Option<Key> {
+    loop {
+        match input.recv().ok()? {
+            // arrow keys arrive as `ESC [ A-D`.
+            0x1b => {
+                let seq = match input.recv_timeout(ESCAPE_TIMEOUT) {
+                    Ok(b'[') => input.recv_timeout(ESCAPE_TIMEOUT).ok(),
+                    Ok(_) => None,
+                    Err(RecvTimeoutError::Timeout) => return Some(Key::Escape),
+                    Err(RecvTimeoutError::Disconnected) => return None,
+                };
+                match seq {
+                    Some(b'A') => return Some(Key::Up),
+                    Some(b'B') => return Some(Key::Down),
+                    Some(b'C') => return Some(Key::Right),
+                    Some(b'D') => return Some(Key::Left),
+                    _ => continue,
+                }
+            }
+            b'\n' | b'\r' => continue,
+            c if c.is_ascii() => return Some(Key::Char(char::from(c))),
+            // only ASCII keys are bound, the bytes of other characters are skipped.
+            _ => continue,
+        }
+    }
+}
+
+/// Extracts the answers accepted by the website from a puzzle description saved by aoc-cli.
+fn parse_known_answers(puzzle: &str) -> [Option<String>; 2] {
+    let mut answers = puzzle
+        .split("Your puzzle answer was")
+        .skip(1)
+        .filter_map(|s| s.split('`').nth(1).map(ToString::to_string));
+
+    [answers.next(), answers.next()]
+}
+
+/// Reads the last recorded timings from the benchmark table in the readme.
+fn parse_readme_timings(readme: &str) -> Vec<(Day, [Option<String>; 2])> {
+    readme
+        .lines()
+        .filter_map(|line| {
+            let day = line
+                .strip_prefix("| [Day ")?
+                .split(']')
+                .next()?
+                .parse::<u8>()
+                .ok()
+                .and_then(Day::new)?;
+
+            let mut timings = line
+                .split('`')
+                .skip(1)
+                .step_by(2)
+                .map(|t| (t != "-").then(|| t.to_string()));
+
+            Some((day, [timings.next().flatten(), timings.next().flatten()]))
+        })
+        .collect()
+}
+
+fn glyph(value: Option<bool>) -> &'static str {
+    match value {
+        Some(true) => "✔",
+        Some(false) => "✖",
+        None => "·",
+    }
+}
+
+fn flag(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn render(statuses: &[DayStatus], selected: usize, is_raw: bool) {
+    let mut out = String::from(ANSI_CLEAR);
+
+    out.push_str(&format!(
+        "{ANSI_BOLD}Advent of Code Dashboard{ANSI_RESET}\n\n"
+    ));
+
+    for (i, (day, status)) in all_days().zip(statuses).enumerate() {
+        let label = if status.scaffolded {
+            format!("{ANSI_BOLD}{day}{ANSI_RESET}")
+        } else {
+            format!("{ANSI_ITALIC}{day}{ANSI_RESET}")
+        };
+
+        let (open, close) = if i == selected {
+            ('[', ']')
+        } else {
+            (' ', ' ')
+        };
+
+        out.push_str(&format!(
+            "{open}{label} {}{}{}{close}  ",
+            glyph(status.tests),
+            glyph(status.verified[0]),
+            glyph(status.verified[1]),
+        ));
+
+        if (i + 1) % COLUMNS == 0 {
+            out.push('\n');
+        }
+    }
+
+    let day = Day::new(selected as u8 + 1).unwrap();
+    let status = &statuses[selected];
+    let none = String::from("-");
+
+    out.push_str(&format!("\n{ANSI_BOLD}Day {day}{ANSI_RESET}\n"));
+    out.push_str(&format!("  scaffolded: {}\n", flag(status.scaffolded)));
+    out.push_str(&format!("  input:      {}\n", flag(status.has_input)));
+    out.push_str(&format!("  examples:   {}\n", flag(status.has_examples)));
+    out.push_str(&format!("  tests:      {}\n", glyph(status.tests)));
+
+    for part in 0..2 {
+        out.push_str(&format!(
+            "  part {}:     {} answer: {} timing: {}\n",
+            part + 1,
+            glyph(status.verified[part]),
+            status.answers[part].as_ref().unwrap_or(&none),
+            status.timings[part].as_ref().unwrap_or(&none),
+        ));
+    }
+
+    out.push_str(&format!(
+        "\n{ANSI_ITALIC}arrows/hjkl: move  t: test  s: solve  b: bench  r: reload  q/esc: quit{ANSI_RESET}\n"
+    ));
+
+    if !is_raw {
+        out.push_str("(confirm each key with enter)\n");
+    }
+
+    print!("{out}");
+    let _ = stdout().flush();
+}
+
+fn run_tests(day: Day) -> bool {
+    Command::new("cargo")
+        .args(["test", "--quiet", "--bin", &day.to_string()])
+        // stdin is read by the dashboard, see `spawn_stdin_reader`.
+        .stdin(Stdio::null())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+fn load_statuses() -> Vec<DayStatus> {
+    let readme_timings = fs::read_to_string(&config::get().paths.readme)
+        .map(|readme| parse_readme_timings(&readme))
+        .unwrap_or_default();
+
+    all_days()
+        .map(|day| DayStatus::load(day, &readme_timings))
+        .collect()
+}
+
+pub fn handle() {
+    let mut statuses = load_statuses();
+    let mut selected = 0;
+    let mut terminal = RawTerminal::enter();
+    let input = spawn_stdin_reader();
+
+    loop {
+        render(&statuses, selected, terminal.is_raw());
+
+        let Some(key) = read_key(&input) else {
+            break;
+        };
+
+        let day = Day::new(selected as u8 + 1).unwrap();
+
+        match key {
+            Key::Up | Key::Char('k') => selected = selected.saturating_sub(COLUMNS),
+            Key::Down | Key::Char('j') => selected = (selected + COLUMNS).min(24),
+            Key::Left | Key::Char('h') => selected = selected.saturating_sub(1),
+            Key::Right | Key::Char('l') => selected = (selected + 1).min(24),
+            Key::Char('r') => statuses = load_statuses(),
+            Key::Escape | Key::Char('q') => break,
+            Key::Char(c @ ('t' | 's' | 'b')) => {
+                if !statuses[selected].scaffolded {
+                    continue;
+                }
+
+                // hand the terminal back to child commands while they run.
+                drop(terminal);
+                print!("{ANSI_CLEAR}");
+
+                let status = &mut statuses[selected];
+
+                match c {
+                    't' => status.tests = Some(run_tests(day)),
+                    _ => {
+                        let is_timed = c == 'b';
+                        match child_commands::run_solution(day, is_timed, true) {
+                            Ok(reports) => {
+                                status.verify(&reports);
+                                if is_timed {
+                                    let environment =
+                                        Environment::detect(true, config::get().bench.pin_cpu);
+                                    let timings =
+                                        child_commands::timings(&reports, day, &environment);
+                                    status.timings = [timings.part_1, timings.part_2];
+                                }
+                            }
+                            Err(e) => eprintln!("Failed to run solution: {e:?}"),
+                        }
+                    }
+                }
+
+                terminal = RawTerminal::enter();
+                discard_keys(&input);
+                println!("\n{ANSI_ITALIC}press any key to return{ANSI_RESET}");
+                if read_key(&input).is_none() {
+                    break;
+                }
+            }
+            Key::Char(_) => {}
+        }
+    }
+
+    print!("{ANSI_CLEAR}");
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_known_answers, parse_readme_timings, DayStatus};
+    use crate::day;
+    use crate::template::runner::RunReport;
+
+    #[test]
+    fn parses_known_answers() {
+        let puzzle = "## --- Part One ---\nYour puzzle answer was `142`.\n## --- Part Two ---\nYour puzzle answer was `281`.";
+        assert_eq!(
+            parse_known_answers(puzzle),
+            [Some("142".into()), Some("281".into())]
+        );
+        assert_eq!(parse_known_answers("# no answers"), [None, None]);
+    }
+
+    #[test]
+    fn verifies_reported_answers() {
+        let mut status = DayStatus {
+            answers: [Some("142".into()), Some("281".into())],
+            ..DayStatus::default()
+        };
+        let report = |part: u8, answer: &str| RunReport {
+            answer: Some(answer.into()),
+            ..RunReport::skipped(day!(1), part)
+        };
+        status.verify(&[report(1, "142"), report(2, "42")]);
+        assert_eq!(status.verified, [Some(true), Some(false)]);
+    }
+
+    #[test]
+    fn parses_readme_timings() {
+        let readme = "| Day | Part 1 | Part 2 | Environment |\n| [Day 1](./src/bin/01.rs) | `10ms` | `-` | Ryzen (16 threads) |";
+        assert_eq!(
+            parse_readme_timings(readme),
+            vec![(day!(1), [Some("10ms".into()), None])]
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use std::process::{self, Command, Stdio};
+
+use crate::Day;
+
+pub fn handle(day: Day, iterations: Option<usize>, seed: Option<u64>, timeout: Option<u64>) {
+    // fuzz a debug build so integer overflows are caught as panics.
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--quiet".to_string(),
+        "--bin".to_string(),
+        day.to_string(),
+        "--".to_string(),
+        "--fuzz".to_string(),
+    ];
+
+    if let Some(iterations) = iterations {
+        cmd_args.push("--iterations".to_string());
+        cmd_args.push(iterations.to_string());
+    }
+
+    if let Some(seed) = seed {
+        cmd_args.push("--seed".to_string());
+        cmd_args.push(seed.to_string());
+    }
+
+    if let Some(timeout) = timeout {
+        cmd_args.push("--timeout".to_string());
+        cmd_args.push(timeout.to_string());
+    }
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    let status = cmd.wait().unwrap();
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+}
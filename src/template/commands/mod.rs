@@ -1,5 +1,11 @@
 pub mod all;
+pub mod check_secrets;
+pub mod countdown;
+pub mod dashboard;
 pub mod download;
+pub mod fuzz;
+pub mod profile;
 pub mod read;
+pub mod review;
 pub mod scaffold;
 pub mod solve;
@@ -0,0 +1,150 @@
+/// Profiles a day and writes a flamegraph per part to `data/profiles/<day>/`.
+/// The solution is run with `--profile`, so the samples are taken from the same loop that `--time` benches.
+/// Samples are recorded with `perf` if it is installed, otherwise with the built-in sampler of the solution binary.
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+};
+
+use crate::template::{
+    config,
+    flamegraph::{self, Folded},
+};
+use crate::Day;
+
+/// Sampling frequency in Hz. An odd number avoids sampling in lockstep with periodic work.
+const FREQUENCY: &str = "997";
+
+/// Profiled builds need debug info to resolve symbols, and frame pointers for the built-in sampler to find callers.
+/// A separate target dir keeps them from invalidating the regular release build.
+const TARGET_DIR: &str = "target/profile";
+const FRAME_POINTERS: &str = "-C force-frame-pointers=yes";
+
+pub fn handle(day: Day, part: Option<u8>) {
+    let has_perf = Command::new("perf")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if !has_perf {
+        println!("\"perf\" not found, using the built-in sampler. Install \"perf\" (e.g. \"linux-tools\") for more accurate profiles.");
+    }
+
+    let status = Command::new("cargo")
+        .args(["build", "--quiet", "--release", "--target-dir", TARGET_DIR])
+        .args(["--bin", &day.to_string()])
+        .env("CARGO_PROFILE_RELEASE_DEBUG", "true")
+        .env("RUSTFLAGS", rustflags())
+        .status();
+    if !status.is_ok_and(|s| s.success()) {
+        eprintln!("Failed to build day {day}.");
+        process::exit(1);
+    }
+
+    let folder = config::get().data_dir("profiles").join(day.to_string());
+    if let Err(e) = fs::create_dir_all(&folder) {
+        eprintln!("Failed to create {}: {e}", folder.display());
+        process::exit(1);
+    }
+
+    let binary = Path::new(TARGET_DIR).join("release").join(day.to_string());
+
+    for part in part.map_or(vec![1, 2], |part| vec![part]) {
+        match profile_part(&binary, &folder, day, part, has_perf) {
+            Ok(svg) => println!("Flamegraph: {}", svg.display()),
+            Err(e) => {
+                eprintln!("Failed to profile part {part}: {e}");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Adds frame pointers to the `RUSTFLAGS` the template is run with.
+fn rustflags() -> String {
+    match env::var("RUSTFLAGS") {
+        Ok(flags) if !flags.trim().is_empty() => format!("{flags} {FRAME_POINTERS}"),
+        _ => FRAME_POINTERS.to_string(),
+    }
+}
+
+/// Records part `part`, then writes its folded stacks and flamegraph. Returns the path of the flamegraph.
+fn profile_part(
+    binary: &Path,
+    folder: &Path,
+    day: Day,
+    part: u8,
+    has_perf: bool,
+) -> Result<PathBuf, String> {
+    let stacks = folder.join(format!("part-{part}.folded"));
+    let svg = folder.join(format!("part-{part}.svg"));
+
+    let folded = if has_perf {
+        record_perf(binary, folder, part)?
+    } else {
+        record_sampler(binary, &stacks, part)?
+    };
+    if folded.is_empty() {
+        return Err("no samples were recorded".into());
+    }
+
+    let title = format!("Day {day}, part {part}");
+    fs::write(&stacks, flamegraph::format_folded(&folded)).map_err(|e| e.to_string())?;
+    fs::write(&svg, flamegraph::render_svg(&folded, &title)).map_err(|e| e.to_string())?;
+
+    Ok(svg)
+}
+
+fn record_perf(binary: &Path, folder: &Path, part: u8) -> Result<Folded, String> {
+    let data = folder.join(format!("part-{part}.perf.data"));
+
+    let status = Command::new("perf")
+        .args([
+            "record",
+            "--quiet",
+            "-F",
+            FREQUENCY,
+            "--call-graph",
+            "dwarf",
+            "-o",
+        ])
+        .arg(&data)
+        .arg("--")
+        .arg(binary)
+        .args(["--profile", "--part", &part.to_string()])
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("\"perf record\" exited with {status}"));
+    }
+
+    let output = Command::new("perf")
+        .args(["script", "-i"])
+        .arg(&data)
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| e.to_string())?;
+    // perf.data holds a copy of the stack of every sample, it is not worth keeping around.
+    let _ = fs::remove_file(&data);
+
+    Ok(flamegraph::collapse_perf(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Lets the solution sample itself, it writes the folded stacks to `stacks`.
+fn record_sampler(binary: &Path, stacks: &Path, part: u8) -> Result<Folded, String> {
+    let status = Command::new(binary)
+        .args(["--profile", "--part", &part.to_string(), "--samples"])
+        .arg(stacks)
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("the solution exited with {status}"));
+    }
+
+    let content = fs::read_to_string(stacks).map_err(|e| e.to_string())?;
+    Ok(flamegraph::parse_folded(&content))
+}
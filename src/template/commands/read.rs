@@ -1,9 +1,43 @@
-use std::process;
+use std::{fmt::Write as _, fs, process};
 
-use crate::template::aoc_cli;
+use crate::template::{aoc_cli, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 use crate::Day;
 
-pub fn handle(day: Day) {
+pub(crate) const PART_TWO_HEADING: &str = "## --- Part Two ---";
+
+pub fn handle(day: Day, part: Option<u8>, examples: bool) {
+    let path = aoc_cli::get_puzzle_path(day);
+
+    let Ok(puzzle) = fs::read_to_string(&path) else {
+        if part.is_some() || examples {
+            eprintln!("Puzzle for day {day} has not been downloaded yet. Try running \"cargo download {day}\".");
+            process::exit(1);
+        }
+        // nothing cached yet, let aoc-cli fetch the description.
+        return read_with_aoc_cli(day);
+    };
+
+    let puzzle = match part {
+        None => puzzle.as_str(),
+        Some(part) => match select_part(&puzzle, part) {
+            Some(text) => text,
+            None => {
+                eprintln!("Part {part} of day {day} is not available (yet).");
+                process::exit(1);
+            }
+        },
+    };
+
+    if examples {
+        for block in code_blocks(puzzle) {
+            println!("{block}");
+        }
+    } else {
+        print!("{}", render(puzzle));
+    }
+}
+
+fn read_with_aoc_cli(day: Day) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
@@ -14,3 +48,176 @@ pub fn handle(day: Day) {
         process::exit(1);
     };
 }
+
+/// Returns the text of one part of a puzzle description. Part two starts at its heading.
+fn select_part(puzzle: &str, part: u8) -> Option<&str> {
+    let split = puzzle.find(PART_TWO_HEADING);
+    match (part, split) {
+        (1, Some(i)) => Some(&puzzle[..i]),
+        (1, None) => Some(puzzle),
+        (2, Some(i)) => Some(&puzzle[i..]),
+        _ => None,
+    }
+}
+
+/// Returns the contents of all fenced code blocks, which hold the examples of a puzzle.
+fn code_blocks(markdown: &str) -> Vec<String> {
+    let mut blocks = vec![];
+    let mut current: Option<String> = None;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                Some(block) => blocks.push(block),
+                None => current = Some(String::new()),
+            }
+        } else if let Some(block) = current.as_mut() {
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+
+    blocks
+}
+
+/// Renders the subset of markdown used by puzzle descriptions with ANSI escape codes:
+/// headings, emphasis, inline code, links, lists and fenced code blocks.
+fn render(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if in_code_block {
+            // examples are shown verbatim, as `*` is common in puzzle inputs.
+            let _ = writeln!(out, "    {line}");
+        } else if let Some(heading) = line.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim();
+            let _ = writeln!(
+                out,
+                "{ANSI_BOLD}{}{ANSI_RESET}",
+                render_inline(heading, ANSI_BOLD)
+            );
+        } else if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            let _ = writeln!(out, "  • {}", render_inline(item, ""));
+        } else {
+            let _ = writeln!(out, "{}", render_inline(line, ""));
+        }
+    }
+
+    out
+}
+
+/// Renders emphasis (`*em*`, `**strong**`), inline code and links within a line.
+/// `outer` is the style that is active around the text and restored after each styled span.
+/// Styles that are already active are not emitted again.
+fn render_inline(text: &str, outer: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let span = [("`", ANSI_ITALIC), ("**", ANSI_BOLD), ("*", ANSI_BOLD)]
+            .iter()
+            .find_map(|(marker, style)| {
+                let inner = rest.strip_prefix(marker)?;
+                let end = inner.find(marker).filter(|&end| end > 0)?;
+                Some((&inner[..end], &inner[end + marker.len()..], *style))
+            });
+
+        if let Some((inner, after, style)) = span {
+            if outer.contains(style) {
+                out.push_str(&render_inline(inner, outer));
+            } else {
+                let styles = format!("{outer}{style}");
+                let _ = write!(
+                    out,
+                    "{style}{}{ANSI_RESET}{outer}",
+                    render_inline(inner, &styles)
+                );
+            }
+            rest = after;
+            continue;
+        }
+
+        if let Some((link, after)) = parse_link(rest) {
+            out.push_str(&render_inline(link, outer));
+            rest = after;
+            continue;
+        }
+
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    out
+}
+
+/// Parses `[text](url)` at the start of `text` and returns the link text and the remaining text.
+fn parse_link(text: &str) -> Option<(&str, &str)> {
+    let inner = text.strip_prefix('[')?;
+    let (label, rest) = inner.split_once("](")?;
+    let (_, rest) = rest.split_once(')')?;
+    Some((label, rest))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{code_blocks, render, select_part};
+    use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+    const PUZZLE: &str = "## --- Day 1: Test ---
+
+For [example](https://example.com):
+
+```
+1abc2
+*x*
+```
+
+Adding these produces `*142*`.
+
+## --- Part Two ---
+
+- one
+- *two*
+";
+
+    #[test]
+    fn selects_parts() {
+        assert!(select_part(PUZZLE, 1).unwrap().ends_with("`*142*`.\n\n"));
+        assert!(select_part(PUZZLE, 2)
+            .unwrap()
+            .starts_with("## --- Part Two"));
+        assert_eq!(select_part("## --- Day 1 ---", 2), None);
+    }
+
+    #[test]
+    fn extracts_examples() {
+        assert_eq!(code_blocks(PUZZLE), vec!["1abc2\n*x*\n"]);
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let rendered = render(PUZZLE);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("{ANSI_BOLD}--- Day 1: Test ---{ANSI_RESET}")
+        );
+        assert_eq!(lines[2], "For example:");
+        assert_eq!(lines[5], "    *x*");
+        assert_eq!(
+            lines[7],
+            format!("Adding these produces {ANSI_ITALIC}{ANSI_BOLD}142{ANSI_RESET}{ANSI_ITALIC}{ANSI_RESET}.")
+        );
+        assert_eq!(
+            render("# *Bold* **`code`**"),
+            format!("{ANSI_BOLD}Bold {ANSI_ITALIC}code{ANSI_RESET}{ANSI_BOLD}{ANSI_RESET}\n")
+        );
+        assert_eq!(lines[12], format!("  • {ANSI_BOLD}two{ANSI_RESET}"));
+    }
+}
@@ -0,0 +1,75 @@
+/// Reviews snapshots that changed in the last test run, see [`crate::template::snapshot`].
+use std::{
+    fs,
+    io::{self, stdin, stdout, BufRead, Write},
+    process,
+};
+
+use crate::template::{snapshot, ANSI_BOLD, ANSI_RESET};
+
+#[derive(Clone, Copy)]
+enum Decision {
+    Accept,
+    Reject,
+    Skip,
+}
+
+fn prompt() -> io::Result<Decision> {
+    loop {
+        print!("accept (a), reject (r) or skip (s)? ");
+        stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(Decision::Skip);
+        }
+        match line.trim() {
+            "a" => return Ok(Decision::Accept),
+            "r" => return Ok(Decision::Reject),
+            "s" => return Ok(Decision::Skip),
+            _ => {}
+        }
+    }
+}
+
+/// Shows the diff of every pending snapshot and accepts or rejects it, either as passed or as chosen interactively.
+pub fn handle(accept_all: bool, reject_all: bool) {
+    let names = snapshot::pending().unwrap_or_else(|e| {
+        eprintln!("Failed to read {}: {e}", snapshot::folder().display());
+        process::exit(1);
+    });
+
+    if names.is_empty() {
+        println!("No snapshots to review.");
+        return;
+    }
+
+    for name in names {
+        let expected = fs::read_to_string(snapshot::path(&name)).ok();
+        let Ok(actual) = fs::read_to_string(snapshot::pending_path(&name)) else {
+            continue;
+        };
+
+        println!("{ANSI_BOLD}{name}{ANSI_RESET}");
+        match expected {
+            Some(expected) => print!("{}", snapshot::diff(&expected, &actual)),
+            None => print!("new snapshot:\n{actual}"),
+        }
+
+        let decision = match (accept_all, reject_all) {
+            (true, _) => Decision::Accept,
+            (_, true) => Decision::Reject,
+            _ => prompt().unwrap_or(Decision::Skip),
+        };
+
+        let result = match decision {
+            Decision::Accept => snapshot::accept(&name).map(|()| "accepted"),
+            Decision::Reject => snapshot::reject(&name).map(|()| "rejected"),
+            Decision::Skip => Ok("skipped"),
+        };
+        match result {
+            Ok(outcome) => println!("{outcome}\n"),
+            Err(e) => eprintln!("Failed to update snapshot {name}: {e}\n"),
+        }
+    }
+}
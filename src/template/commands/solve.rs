@@ -1,8 +1,9 @@
-use std::process::{Command, Stdio};
+use std::process::{self, Command, Stdio};
 
+use crate::template::options::RunOptions;
 use crate::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
+pub fn handle(day: Day, release: bool, options: &RunOptions) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if release {
@@ -10,15 +11,7 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
     }
 
     cmd_args.push("--".to_string());
-
-    if let Some(submit_part) = submit_part {
-        cmd_args.push("--submit".to_string());
-        cmd_args.push(submit_part.to_string());
-    }
-
-    if time {
-        cmd_args.push("--time".to_string());
-    }
+    cmd_args.extend(options.to_args());
 
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
@@ -27,5 +20,9 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
         .spawn()
         .unwrap();
 
-    cmd.wait().unwrap();
+    // e.g. `--variants` fails if the variants disagree.
+    let status = cmd.wait().unwrap();
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
 }
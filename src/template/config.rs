@@ -0,0 +1,431 @@
+/// Project configuration, read from `aoc.toml` in the project root.
+/// Every key can be overridden by an environment variable named `AOC_<SECTION>_<KEY>`, e.g. `AOC_YEAR` or
+/// `AOC_PATHS_INPUTS`.
+use std::{
+    env,
+    fmt::{self, Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+    process,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use serde::{de::Error as _, Deserialize, Deserializer};
+use toml::{Table, Value};
+
+pub const CONFIG_PATH: &str = "aoc.toml";
+
+/// Where the template reads and writes its files.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Paths {
+    /// Root for generated data, e.g. the download cache and fuzz reproducers.
+    #[serde(deserialize_with = "path")]
+    pub data: PathBuf,
+    #[serde(deserialize_with = "path")]
+    pub inputs: PathBuf,
+    #[serde(deserialize_with = "path")]
+    pub puzzles: PathBuf,
+    #[serde(deserialize_with = "path")]
+    pub examples: PathBuf,
+    /// Cargo only discovers binaries in `src/bin` on its own, other folders need `[[bin]]` entries in `Cargo.toml`.
+    #[serde(deserialize_with = "path")]
+    pub bin: PathBuf,
+    #[serde(deserialize_with = "path")]
+    pub readme: PathBuf,
+    /// Git-ignored directory for inputs and puzzles, see [`crate::template::secrets`].
+    #[serde(deserialize_with = "optional_path")]
+    pub store: Option<PathBuf>,
+}
+
+impl Default for Paths {
+    fn default() -> Self {
+        Self {
+            data: "data".into(),
+            inputs: "data/inputs".into(),
+            puzzles: "data/puzzles".into(),
+            examples: "data/examples".into(),
+            bin: "src/bin".into(),
+            readme: "README.md".into(),
+            store: None,
+        }
+    }
+}
+
+/// Flags that are enabled for `cargo solve` and `cargo all` without passing them.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Defaults {
+    pub release: bool,
+    pub time: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Bench {
+    /// Approximate time spent benching each part.
+    #[serde(rename = "target_ms", deserialize_with = "millis")]
+    pub target: Duration,
+    #[serde(deserialize_with = "samples")]
+    pub min_samples: u128,
+    #[serde(deserialize_with = "samples")]
+    pub max_samples: u128,
+    /// Whether `cargo time` writes the results to the readme.
+    pub update_readme: bool,
+    /// CPU core benched solutions are pinned to, see [`crate::template::bench_env::pin_once`].
+    pub pin_cpu: Option<usize>,
+}
+
+impl Default for Bench {
+    fn default() -> Self {
+        Self {
+            target: Duration::from_secs(1),
+            min_samples: 10,
+            max_samples: 10_000,
+            update_readme: true,
+            pin_cpu: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Event year. aoc-cli picks the latest event if it is not set.
+    #[serde(deserialize_with = "year")]
+    pub year: Option<u16>,
+    /// File holding the session token, passed to aoc-cli. aoc-cli looks in `~/.adventofcode.session` if not set.
+    #[serde(rename = "session", deserialize_with = "session_file")]
+    pub session_file: Option<PathBuf>,
+    pub paths: Paths,
+    pub defaults: Defaults,
+    pub bench: Bench,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ConfigError {
+    /// Line of `aoc.toml` or name of the environment variable.
+    origin: String,
+    message: String,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.origin, self.message)
+    }
+}
+
+fn year<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u16>, D::Error> {
+    match i64::deserialize(deserializer)? {
+        year @ 2015..=9999 => Ok(Some(year as u16)),
+        year => Err(D::Error::custom(format!(
+            "`year` must be 2015 or later, found {year}"
+        ))),
+    }
+}
+
+fn session_file<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<PathBuf>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Session {
+        #[serde(default, deserialize_with = "optional_path")]
+        file: Option<PathBuf>,
+    }
+    Ok(Session::deserialize(deserializer)?.file)
+}
+
+fn path<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    match String::deserialize(deserializer)? {
+        path if path.is_empty() => Err(D::Error::custom("path must not be empty")),
+        path => Ok(expand_home(&path)),
+    }
+}
+
+/// An empty string disables an optional path.
+fn optional_path<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<PathBuf>, D::Error> {
+    let path = String::deserialize(deserializer)?;
+    Ok((!path.is_empty()).then(|| expand_home(&path)))
+}
+
+fn millis<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    match u64::deserialize(deserializer)? {
+        0 => Err(D::Error::custom("must be at least 1")),
+        ms => Ok(Duration::from_millis(ms)),
+    }
+}
+
+fn samples<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+    match u64::deserialize(deserializer)? {
+        0 => Err(D::Error::custom("must be at least 1")),
+        n => Ok(u128::from(n)),
+    }
+}
+
+/// Expands a leading `~/` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var("HOME")) {
+        (Some(rest), Ok(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// All keys, in the `section.key` notation used for environment variables.
+const KEYS: [&str; 16] = [
+    "year",
+    "session.file",
+    "paths.data",
+    "paths.inputs",
+    "paths.puzzles",
+    "paths.examples",
+    "paths.bin",
+    "paths.readme",
+    "paths.store",
+    "defaults.release",
+    "defaults.time",
+    "bench.target_ms",
+    "bench.min_samples",
+    "bench.max_samples",
+    "bench.update_readme",
+    "bench.pin_cpu",
+];
+
+/// Name of the environment variable that overrides `key`.
+#[must_use]
+pub fn env_var(key: &str) -> String {
+    format!("AOC_{}", key.replace('.', "_").to_uppercase())
+}
+
+/// Sets `key` in `table`, or removes it to fall back to the default.
+fn set(table: &mut Table, key: &str, value: Option<Value>) {
+    let (table, key) = match key.split_once('.') {
+        Some((section, key)) => {
+            let section = table
+                .entry(section)
+                .or_insert_with(|| Value::Table(Table::new()));
+            // the file was deserialized already, so sections are tables.
+            (section.as_table_mut().unwrap(), key)
+        }
+        None => (table, key),
+    };
+    match value {
+        Some(value) => table.insert(key.to_string(), value),
+        None => table.remove(key),
+    };
+}
+
+/// Overrides `key` with the value of an environment variable.
+fn apply(table: &mut Table, key: &str, raw: String) -> Result<Config, toml::de::Error> {
+    if raw.is_empty() {
+        set(table, key, None);
+        return table.clone().try_into();
+    }
+    // a TOML value can still be meant as a string, e.g. a path like `2024`.
+    let error = match raw.parse::<Value>() {
+        Ok(value) => {
+            set(table, key, Some(value));
+            match table.clone().try_into() {
+                Ok(config) => return Ok(config),
+                Err(e) => Some(e),
+            }
+        }
+        Err(_) => None,
+    };
+    set(table, key, Some(Value::String(raw)));
+    table.clone().try_into().map_err(|e| error.unwrap_or(e))
+}
+
+impl Config {
+    /// Parses the content of `aoc.toml` and applies overrides from `env`.
+    /// Environment variables are read as TOML values, e.g. `true` or `2`, and as strings otherwise. An empty variable
+    /// resets the key to its default.
+    pub fn parse(content: &str, env: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut config: Config = toml::from_str(content).map_err(|e| {
+            let origin = match e.span() {
+                Some(span) => format!(
+                    "{CONFIG_PATH}:{}",
+                    content[..span.start].matches('\n').count() + 1
+                ),
+                None => CONFIG_PATH.to_string(),
+            };
+            ConfigError {
+                origin,
+                message: e.message().to_string(),
+            }
+        })?;
+
+        let mut table: Table = toml::from_str(content).unwrap_or_default();
+        for key in KEYS {
+            let name = env_var(key);
+            let Some(raw) = env(&name) else {
+                continue;
+            };
+
+            config = apply(&mut table, key, raw).map_err(|e| ConfigError {
+                origin: name,
+                message: e.message().to_string(),
+            })?;
+        }
+
+        config.validate().map_err(|message| ConfigError {
+            origin: CONFIG_PATH.to_string(),
+            message,
+        })?;
+        Ok(config)
+    }
+
+    /// Reads `aoc.toml` from the current directory. A missing file yields the defaults.
+    pub fn load() -> Result<Self, ConfigError> {
+        let content = match fs::read_to_string(CONFIG_PATH) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(ConfigError {
+                    origin: CONFIG_PATH.to_string(),
+                    message: e.to_string(),
+                })
+            }
+        };
+        Self::parse(&content, |name| env::var(name).ok())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.bench.min_samples > self.bench.max_samples {
+            return Err(format!(
+                "`bench.min_samples` ({}) must not exceed `bench.max_samples` ({})",
+                self.bench.min_samples, self.bench.max_samples
+            ));
+        }
+        Ok(())
+    }
+
+    /// The directory of a data folder, e.g. `inputs`. Folders without a key of their own live below `paths.data`.
+    #[must_use]
+    pub fn data_dir(&self, folder: &str) -> PathBuf {
+        match folder {
+            "inputs" => self.paths.inputs.clone(),
+            "puzzles" => self.paths.puzzles.clone(),
+            "examples" => self.paths.examples.clone(),
+            folder => self.paths.data.join(folder),
+        }
+    }
+
+    #[must_use]
+    pub fn bin_path(&self, day: crate::Day) -> PathBuf {
+        self.paths.bin.join(format!("{day}.rs"))
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The configuration of this project, loaded once. Exits with an error message if it is invalid.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        Config::load().unwrap_or_else(|e| {
+            eprintln!("Invalid configuration: {e}");
+            process::exit(1);
+        })
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::{Config, ConfigError};
+
+    fn parse(content: &str) -> Result<Config, ConfigError> {
+        Config::parse(content, |_| None)
+    }
+
+    #[test]
+    fn parses_config() {
+        let config = parse(
+            r#"
+# comment
+year = 2023
+
+[paths]
+inputs = "inputs/#1" # trailing comment
+store = ""
+
+[bench]
+target_ms = 2_000
+update_readme = false
+pin_cpu = 3
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.year, Some(2023));
+        assert_eq!(config.paths.inputs, PathBuf::from("inputs/#1"));
+        assert_eq!(config.paths.store, None);
+        assert_eq!(config.paths.bin, PathBuf::from("src/bin"));
+        assert_eq!(config.bench.target, Duration::from_secs(2));
+        assert!(!config.bench.update_readme);
+        assert_eq!(config.bench.pin_cpu, Some(3));
+        assert_eq!(parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn applies_env_overrides() {
+        let config = Config::parse("year = 2022\n[bench]\npin_cpu = 1\n", |name| match name {
+            "AOC_YEAR" => Some("2023".into()),
+            "AOC_BENCH_PIN_CPU" => Some(String::new()),
+            "AOC_DEFAULTS_RELEASE" => Some("true".into()),
+            "AOC_PATHS_STORE" => Some(".aoc-store".into()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(config.year, Some(2023));
+        assert!(config.defaults.release);
+        assert_eq!(config.paths.store, Some(PathBuf::from(".aoc-store")));
+        assert_eq!(config.bench.pin_cpu, None);
+
+        let config = Config::parse("", |name| (name == "AOC_PATHS_DATA").then(|| "2024".into()));
+        assert_eq!(config.unwrap().paths.data, PathBuf::from("2024"));
+        let err = Config::parse("", |name| {
+            (name == "AOC_BENCH_TARGET_MS").then(|| "0".into())
+        });
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "AOC_BENCH_TARGET_MS: must be at least 1"
+        );
+
+        let err = Config::parse("", |name| (name == "AOC_YEAR").then(|| "soon".into()));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "AOC_YEAR: invalid type: string \"soon\", expected i64"
+        );
+    }
+
+    #[test]
+    fn reports_invalid_config() {
+        let error = |content: &str| parse(content).unwrap_err().to_string();
+        assert_eq!(
+            error("\nyaer = 2023"),
+            "aoc.toml:2: unknown field `yaer`, expected one of `year`, `session`, `paths`, `defaults`, `bench`"
+        );
+        assert_eq!(
+            error("year = 1999"),
+            "aoc.toml:1: `year` must be 2015 or later, found 1999"
+        );
+        assert_eq!(
+            error("year = \"2023\""),
+            "aoc.toml:1: invalid type: string \"2023\", expected i64"
+        );
+        assert_eq!(error("year"), "aoc.toml:1: key with no value, expected `=`");
+        assert_eq!(
+            error("year = 2023\nyear = 2024"),
+            "aoc.toml:2: duplicate key"
+        );
+        assert_eq!(
+            error("[bench]\nmin_samples = 0"),
+            "aoc.toml:2: must be at least 1"
+        );
+        assert_eq!(
+            error("[bench]\nmin_samples = 20\nmax_samples = 10"),
+            "aoc.toml: `bench.min_samples` (20) must not exceed `bench.max_samples` (10)"
+        );
+    }
+}
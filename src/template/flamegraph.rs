@@ -0,0 +1,258 @@
+/// Turns sampled call stacks into flamegraphs, see `cargo profile`.
+/// Stacks are collapsed into the "folded" format of Brendan Gregg's flamegraph tools (`a;b;c 42`),
+/// so they can also be fed into other tools like `inferno` or speedscope.
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use super::{hash::fnv1a, viz::escape_xml};
+
+/// Number of samples per call stack, keyed by the frames from root to leaf joined with `;`.
+pub type Folded = BTreeMap<String, u64>;
+
+const WIDTH: f64 = 1200.0;
+const FRAME_HEIGHT: usize = 16;
+const PADDING: usize = 32;
+/// Frames narrower than this are not drawn.
+const MIN_WIDTH: f64 = 0.1;
+/// Approximate width of a character of the 12px monospace font, to truncate labels.
+const CHAR_WIDTH: f64 = 7.2;
+
+/// Counts identical call stacks, given as frames from leaf to root like a backtrace.
+/// Frames containing `;` would break the folded format and are left out.
+#[must_use]
+pub fn collapse<S: AsRef<str>>(stacks: impl IntoIterator<Item = Vec<S>>) -> Folded {
+    let mut folded = Folded::new();
+    for stack in stacks {
+        let frames: Vec<&str> = stack
+            .iter()
+            .rev()
+            .map(AsRef::as_ref)
+            .filter(|frame| !frame.contains(';'))
+            .collect();
+        if !frames.is_empty() {
+            *folded.entry(frames.join(";")).or_default() += 1;
+        }
+    }
+    folded
+}
+
+/// Collapses the output of `perf script` into folded stacks.
+/// Each sample is a header line followed by one indented line per frame, leaf first, and ends with a blank line.
+#[must_use]
+pub fn collapse_perf(script: &str) -> Folded {
+    let mut stacks = vec![];
+    let mut frames: Vec<&str> = vec![];
+
+    for line in script.lines().chain([""]) {
+        if line.trim().is_empty() {
+            if !frames.is_empty() {
+                stacks.push(std::mem::take(&mut frames));
+            }
+        } else if line.starts_with(char::is_whitespace) {
+            frames.extend(parse_frame(line));
+        }
+    }
+
+    collapse(stacks)
+}
+
+/// Extracts the symbol of a frame line like `55d4c0a1b2c3 _01::part_one+0x23 (/path/to/01)`.
+fn parse_frame(line: &str) -> Option<&str> {
+    let (_address, rest) = line.trim().split_once(char::is_whitespace)?;
+    let symbol = rest.rsplit_once(" (").map_or(rest, |(symbol, _dso)| symbol);
+    let symbol = symbol
+        .rsplit_once("+0x")
+        .map_or(symbol, |(symbol, _)| symbol);
+    Some(symbol.trim())
+}
+
+#[must_use]
+pub fn format_folded(folded: &Folded) -> String {
+    folded
+        .iter()
+        .map(|(stack, count)| format!("{stack} {count}\n"))
+        .collect()
+}
+
+/// Reads folded stacks written by [`format_folded`] or other tools. Invalid lines are skipped.
+#[must_use]
+pub fn parse_folded(content: &str) -> Folded {
+    let mut folded = Folded::new();
+    for (stack, count) in content
+        .lines()
+        .filter_map(|line| line.rsplit_once(' '))
+        .filter_map(|(stack, count)| Some((stack, count.parse::<u64>().ok()?)))
+    {
+        *folded.entry(stack.to_string()).or_default() += count;
+    }
+    folded
+}
+
+#[derive(Default)]
+struct Node {
+    samples: u64,
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn insert(&mut self, stack: &str, samples: u64) {
+        self.samples += samples;
+        let mut node = self;
+        for frame in stack.split(';') {
+            node = node.children.entry(frame.to_string()).or_default();
+            node.samples += samples;
+        }
+    }
+
+    fn depth(&self) -> usize {
+        self.children
+            .values()
+            .map(|c| c.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Renders folded stacks as an SVG flamegraph: callers at the bottom, frame widths proportional to their samples.
+/// Hovering a frame shows its full name and share of the samples.
+#[must_use]
+pub fn render_svg(folded: &Folded, title: &str) -> String {
+    let mut root = Node::default();
+    for (stack, samples) in folded {
+        root.insert(stack, *samples);
+    }
+
+    let height = root.depth() * FRAME_HEIGHT + 2 * PADDING;
+    let mut out = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" font-family="monospace" font-size="12">"#
+    );
+    out.push('\n');
+    let _ = writeln!(
+        out,
+        r#"<text x="{}" y="20" text-anchor="middle" font-size="16">{}</text>"#,
+        WIDTH / 2.0,
+        escape_xml(title)
+    );
+
+    let total = root.samples.max(1);
+    let mut x = 0.0;
+    for (name, node) in &root.children {
+        x += render_node(&mut out, name, node, x, 0, total, height);
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Draws a frame and its callees, returns the width of the frame.
+fn render_node(
+    out: &mut String,
+    name: &str,
+    node: &Node,
+    x: f64,
+    depth: usize,
+    total: u64,
+    height: usize,
+) -> f64 {
+    let width = node.samples as f64 / total as f64 * WIDTH;
+    if width < MIN_WIDTH {
+        return width;
+    }
+
+    let y = height - PADDING - (depth + 1) * FRAME_HEIGHT;
+    let percent = node.samples as f64 / total as f64 * 100.0;
+    let escaped = escape_xml(name);
+    let _ = write!(
+        out,
+        r#"<g><title>{escaped} ({} samples, {percent:.2}%)</title><rect x="{x:.1}" y="{y}" width="{width:.1}" height="{}" fill="{}" />"#,
+        node.samples,
+        FRAME_HEIGHT - 1,
+        colour(name)
+    );
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let chars = ((width - 6.0) / CHAR_WIDTH) as usize;
+    if chars >= 3 {
+        let label: String = if name.chars().count() > chars {
+            name.chars().take(chars - 2).chain("..".chars()).collect()
+        } else {
+            name.to_string()
+        };
+        let _ = write!(
+            out,
+            r#"<text x="{:.1}" y="{}">{}</text>"#,
+            x + 3.0,
+            y + FRAME_HEIGHT - 4,
+            escape_xml(&label)
+        );
+    }
+    out.push_str("</g>\n");
+
+    let mut child_x = x;
+    for (child, child_node) in &node.children {
+        child_x += render_node(out, child, child_node, child_x, depth + 1, total, height);
+    }
+
+    width
+}
+
+/// A warm colour derived from the frame name, so a function has the same colour across flamegraphs.
+fn colour(name: &str) -> String {
+    let hash = fnv1a(name.as_bytes());
+    format!(
+        "rgb({},{},{})",
+        205 + hash % 50,
+        (hash >> 8) % 180,
+        (hash >> 16) % 55
+    )
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{collapse, collapse_perf, format_folded, parse_folded, render_svg};
+
+    const SCRIPT: &str = "\
+01 123 1.0:     1001 cycles:u:
+\t    55d4c0a1b2c3 _01::part_one+0x23 (/target/release/01)
+\t    55d4c0a1b2c4 _01::main+0x10 (/target/release/01)
+
+01 123 1.1:     1001 cycles:u:
+\t    55d4c0a1b2c5 <core::str::Lines as core::iter::Iterator>::next+0x5 (/target/release/01)
+\t    55d4c0a1b2c3 _01::part_one+0x23 (/target/release/01)
+\t    55d4c0a1b2c4 _01::main+0x10 (/target/release/01)
+
+01 123 1.2:     1001 cycles:u:
+\t    55d4c0a1b2c3 _01::part_one+0x40 (/target/release/01)
+\t    55d4c0a1b2c4 _01::main+0x10 (/target/release/01)
+";
+
+    #[test]
+    fn collapses_perf_script() {
+        assert_eq!(
+            format_folded(&collapse_perf(SCRIPT)),
+            "_01::main;_01::part_one 2\n_01::main;_01::part_one;<core::str::Lines as core::iter::Iterator>::next 1\n"
+        );
+    }
+
+    #[test]
+    fn collapses_backtraces() {
+        let folded = collapse([
+            vec!["part_one", "main"],
+            vec!["part_one", "main"],
+            vec!["<[u8; 4]>::eq", "main"],
+        ]);
+        assert_eq!(format_folded(&folded), "main 1\nmain;part_one 2\n");
+        assert_eq!(parse_folded(&format_folded(&folded)), folded);
+        assert_eq!(parse_folded("a;b 2\ninvalid\na;b 1\n").get("a;b"), Some(&3));
+    }
+
+    #[test]
+    fn renders_frames() {
+        let svg = render_svg(&collapse_perf(SCRIPT), "Day 01 <part 1>");
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Day 01 &lt;part 1&gt;"));
+        assert!(svg.contains("<title>_01::main (3 samples, 100.00%)</title>"));
+        assert!(svg.contains("<title>_01::part_one (3 samples, 100.00%)</title>"));
+        assert!(svg.contains(r#"width="400.0""#));
+        assert_eq!(svg.matches("<rect").count(), 3);
+    }
+}
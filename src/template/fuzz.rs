@@ -0,0 +1,382 @@
+/// Fuzzing harness for solution parsers and parts.
+/// Feeds randomly mutated versions of a day's example files into every target and reports panics and hangs,
+/// shrinking each crashing input to a minimal reproducer.
+use std::{
+    collections::HashMap,
+    fs,
+    panic::{self, AssertUnwindSafe},
+    process,
+    sync::{mpsc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use rand::{rngs::StdRng, seq::IndexedRandom, RngExt, SeedableRng};
+
+use super::config;
+use crate::Day;
+
+/// A named function that is fed fuzzed inputs. Results are discarded, only panics and hangs are of interest.
+pub type Target = (&'static str, fn(&str));
+
+/// Location of the last panic, recorded by the panic hook installed while fuzzing.
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+const DEFAULT_ITERATIONS: usize = 1000;
+const DEFAULT_TIMEOUT_MS: u64 = 2000;
+
+/// Characters that are likely to confuse parsers, in addition to the ones found in the example itself.
+const INTERESTING_CHARS: &[char] = &[' ', '\n', ':', ',', '-', '|', '0', '9', 'x', 'é', '\t'];
+const INTERESTING_TOKENS: &[&str] = &[
+    "",
+    "0",
+    "-1",
+    "99999999999999999999",
+    "18446744073709551615",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Ok,
+    Panic { location: String, message: String },
+    Timeout,
+}
+
+impl Outcome {
+    /// Two failures are considered the same if they panic at the same location, regardless of the message.
+    fn is_same_failure(&self, other: &Outcome) -> bool {
+        match (self, other) {
+            (Outcome::Panic { location: a, .. }, Outcome::Panic { location: b, .. }) => a == b,
+            (a, b) => a == b,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Crash {
+    pub target: &'static str,
+    pub outcome: Outcome,
+    pub input: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    pub iterations: usize,
+    pub seed: u64,
+    pub timeout: Duration,
+}
+
+impl Options {
+    /// Options for `--iterations`, `--seed` and `--timeout <ms>`, with defaults for the ones that are not given.
+    #[must_use]
+    pub fn new(iterations: Option<usize>, seed: Option<u64>, timeout_ms: Option<u64>) -> Self {
+        Self {
+            iterations: iterations.unwrap_or(DEFAULT_ITERATIONS),
+            seed: seed.unwrap_or_else(rand::random),
+            timeout: Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS)),
+        }
+    }
+}
+
+/// Runs `target` on a separate thread and classifies the result.
+/// Threads of targets that time out cannot be stopped and keep running in the background.
+#[must_use]
+pub fn check(target: fn(&str), input: &str, timeout: Duration) -> Outcome {
+    let (tx, rx) = mpsc::channel();
+    let input = input.to_string();
+
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| target(&input)));
+        let outcome = match result {
+            Ok(()) => Outcome::Ok,
+            Err(payload) => Outcome::Panic {
+                location: LAST_PANIC
+                    .lock()
+                    .ok()
+                    .and_then(|mut l| l.take())
+                    .unwrap_or_default(),
+                message: payload
+                    .downcast_ref::<&str>()
+                    .map(ToString::to_string)
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default(),
+            },
+        };
+        let _ = tx.send(outcome);
+    });
+
+    rx.recv_timeout(timeout).unwrap_or(Outcome::Timeout)
+}
+
+fn split_lines(input: &str) -> Vec<String> {
+    input
+        .split_inclusive('\n')
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Applies between one and four random mutations to `input`.
+pub fn mutate(input: &str, rng: &mut StdRng) -> String {
+    let mut alphabet: Vec<char> = input.chars().collect();
+    alphabet.sort_unstable();
+    alphabet.dedup();
+    alphabet.extend_from_slice(INTERESTING_CHARS);
+
+    let mut current = input.to_string();
+
+    for _ in 0..rng.random_range(1..=4) {
+        let mut chars: Vec<char> = current.chars().collect();
+        let mut lines = split_lines(&current);
+
+        let is_line_mutation = match rng.random_range(0..8) {
+            0 if !chars.is_empty() => {
+                chars.remove(rng.random_range(0..chars.len()));
+                false
+            }
+            1 => {
+                let c = *alphabet.choose(rng).unwrap();
+                chars.insert(rng.random_range(0..=chars.len()), c);
+                false
+            }
+            2 if !chars.is_empty() => {
+                let i = rng.random_range(0..chars.len());
+                chars[i] = *alphabet.choose(rng).unwrap();
+                false
+            }
+            3 if !lines.is_empty() => {
+                lines.remove(rng.random_range(0..lines.len()));
+                true
+            }
+            4 if !lines.is_empty() => {
+                let i = rng.random_range(0..lines.len());
+                lines.insert(i, lines[i].clone());
+                true
+            }
+            5 if !lines.is_empty() => {
+                let (a, b) = (
+                    rng.random_range(0..lines.len()),
+                    rng.random_range(0..lines.len()),
+                );
+                lines.swap(a, b);
+                true
+            }
+            6 if !chars.is_empty() => {
+                chars.truncate(rng.random_range(0..chars.len()));
+                false
+            }
+            7 => {
+                let token = INTERESTING_TOKENS.choose(rng).unwrap();
+                let i = rng.random_range(0..=chars.len());
+                chars.splice(i..i, token.chars());
+                false
+            }
+            _ => continue,
+        };
+
+        current = if is_line_mutation {
+            lines.concat()
+        } else {
+            chars.into_iter().collect()
+        };
+    }
+
+    current
+}
+
+/// Shrinks `input` while `target` keeps failing with the same outcome.
+/// Whole lines are removed first, then chunks of characters of decreasing size.
+#[must_use]
+pub fn minimize(target: fn(&str), input: &str, outcome: &Outcome, timeout: Duration) -> String {
+    let still_fails = |candidate: &str| check(target, candidate, timeout).is_same_failure(outcome);
+
+    let mut lines = split_lines(input);
+    let mut i = 0;
+    while i < lines.len() {
+        let mut candidate = lines.clone();
+        candidate.remove(i);
+        if still_fails(&candidate.concat()) {
+            lines = candidate;
+        } else {
+            i += 1;
+        }
+    }
+
+    let mut chars: Vec<char> = lines.concat().chars().collect();
+    let mut chunk = chars.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < chars.len() {
+            let end = (start + chunk).min(chars.len());
+            let candidate: String = chars[..start].iter().chain(&chars[end..]).collect();
+            if still_fails(&candidate) {
+                chars.drain(start..end);
+            } else {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+
+    chars.into_iter().collect()
+}
+
+/// Fuzzes every target with mutations of `examples` and returns one minimized crash per distinct failure.
+pub fn fuzz(targets: &[Target], examples: &[String], options: Options) -> Vec<Crash> {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+        if let Ok(mut last) = LAST_PANIC.lock() {
+            *last = info.location().map(ToString::to_string);
+        }
+    }));
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut crashes = vec![];
+
+    for &(name, target) in targets {
+        let mut seen: HashMap<String, Crash> = HashMap::new();
+
+        for i in 0..options.iterations {
+            let example = examples.choose(&mut rng).unwrap();
+            // the unmodified examples are checked first.
+            let input = if i < examples.len() {
+                examples[i].clone()
+            } else {
+                mutate(example, &mut rng)
+            };
+
+            let outcome = check(target, &input, options.timeout);
+            let key = match &outcome {
+                Outcome::Ok => continue,
+                Outcome::Panic { location, .. } => location.clone(),
+                Outcome::Timeout => {
+                    // a hanging thread keeps burning cpu, so stop fuzzing this target.
+                    seen.insert(
+                        "timeout".into(),
+                        Crash {
+                            target: name,
+                            outcome,
+                            input,
+                        },
+                    );
+                    break;
+                }
+            };
+
+            seen.entry(key).or_insert_with(|| {
+                let input = minimize(target, &input, &outcome, options.timeout);
+                // report the message produced by the reproducer rather than the original input.
+                let outcome = check(target, &input, options.timeout);
+                Crash {
+                    target: name,
+                    outcome,
+                    input,
+                }
+            });
+        }
+
+        println!("{name}: {} distinct failure(s)", seen.len());
+        crashes.extend(seen.into_values());
+    }
+
+    panic::set_hook(default_hook);
+    crashes
+}
+
+fn read_examples(day: Day) -> Vec<String> {
+    [
+        format!("{day}.txt"),
+        format!("{day}-1.txt"),
+        format!("{day}-2.txt"),
+    ]
+    .iter()
+    .filter_map(|name| fs::read_to_string(config::get().paths.examples.join(name)).ok())
+    .filter(|s| !s.is_empty())
+    .collect()
+}
+
+/// Entry point used by the `solution!` macro when a solution is invoked with `--fuzz`.
+/// Reproducers are written to `fuzz/<day>/` within the data folder.
+pub fn run(day: Day, targets: &[Target], options: Options) {
+    let examples = read_examples(day);
+
+    if examples.is_empty() {
+        eprintln!("No example files found for day {day}.");
+        process::exit(1);
+    }
+
+    println!(
+        "Fuzzing day {day}: {} iterations per target, seed {}",
+        options.iterations, options.seed
+    );
+
+    let crashes = fuzz(targets, &examples, options);
+
+    if crashes.is_empty() {
+        println!("No failures found.");
+        return;
+    }
+
+    let folder = config::get().paths.data.join("fuzz").join(day.to_string());
+    let _ = fs::create_dir_all(&folder);
+
+    for (i, crash) in crashes.iter().enumerate() {
+        let description = match &crash.outcome {
+            Outcome::Panic { location, message } => format!("panicked at {location}: {message}"),
+            Outcome::Timeout => format!("did not finish within {:?}", options.timeout),
+            Outcome::Ok => unreachable!(),
+        };
+        let file_name = format!(
+            "{}-{i}.txt",
+            crash.target.replace(|c: char| !c.is_alphanumeric(), "_")
+        );
+        let path = folder.join(file_name);
+
+        println!("\n---\n{} {description}", crash.target);
+        println!("reproducer ({}):\n{:?}", path.display(), crash.input);
+
+        if let Err(e) = fs::write(&path, &crash.input) {
+            eprintln!("Failed to write reproducer: {e}");
+        }
+    }
+
+    process::exit(1);
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{check, fuzz, minimize, mutate, Options, Outcome};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::time::Duration;
+
+    fn panics_on_x(input: &str) {
+        assert!(!input.contains('x'), "found x");
+    }
+
+    #[test]
+    fn mutations_are_reproducible() {
+        let a = mutate("Card 1: 1 2 | 3 4\n", &mut StdRng::seed_from_u64(7));
+        let b = mutate("Card 1: 1 2 | 3 4\n", &mut StdRng::seed_from_u64(7));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn minimizes_crashing_input() {
+        let timeout = Duration::from_secs(1);
+        let input = "abc\ndxe\nfgh\n";
+        let outcome = check(panics_on_x, input, timeout);
+        assert!(matches!(outcome, Outcome::Panic { .. }));
+        assert_eq!(minimize(panics_on_x, input, &outcome, timeout), "x");
+    }
+
+    #[test]
+    fn reports_distinct_failures() {
+        let options = Options {
+            iterations: 200,
+            seed: 1,
+            timeout: Duration::from_secs(1),
+        };
+        let crashes = fuzz(&[("panics_on_x", panics_on_x)], &["abc\n".into()], options);
+        assert_eq!(crashes.len(), 1);
+        assert_eq!(crashes[0].input, "x");
+    }
+}
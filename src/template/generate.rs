@@ -0,0 +1,75 @@
+/// Framework for generating random, valid puzzle inputs.
+/// Days register a generator via `solution!(<day>, generate = <fn>)`; generated inputs are used by property tests
+/// that compare a solution against a naive reference implementation.
+use std::fmt::Debug;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Produces a valid puzzle input. The meaning of `size` is up to the day, e.g. the number of lines.
+pub type Generator = fn(&mut StdRng, usize) -> String;
+
+/// Entry point used by the `solution!` macro when a solution is invoked with `--generate <size> [--seed <seed>]`.
+pub fn run(generator: Generator, size: usize, seed: Option<u64>) {
+    let mut rng = seed.map_or_else(rand::make_rng, StdRng::seed_from_u64);
+    print!("{}", generator(&mut rng, size));
+}
+
+/// Runs `cases` generated inputs of every size through `reference` and `solution` and panics on the first mismatch.
+/// The panic message contains the seed, size and input needed to reproduce the failure.
+pub fn compare<T: PartialEq + Debug>(
+    generator: Generator,
+    reference: impl Fn(&str) -> T,
+    solution: impl Fn(&str) -> T,
+    sizes: &[usize],
+    cases: usize,
+    seed: u64,
+) {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for &size in sizes {
+        for case in 0..cases {
+            let input = generator(&mut rng, size);
+            let expected = reference(&input);
+            let actual = solution(&input);
+
+            assert!(
+                expected == actual,
+                "mismatch (seed {seed}, size {size}, case {case})\nreference: {expected:?}\nsolution:  {actual:?}\ninput:\n{input}"
+            );
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::compare;
+    use rand::{rngs::StdRng, RngExt};
+
+    fn numbers(rng: &mut StdRng, size: usize) -> String {
+        (0..size)
+            .map(|_| format!("{}\n", rng.random_range(0..100)))
+            .collect()
+    }
+
+    fn sum(input: &str) -> u64 {
+        input.lines().map(|l| l.parse::<u64>().unwrap()).sum()
+    }
+
+    #[test]
+    fn accepts_matching_implementations() {
+        compare(
+            numbers,
+            sum,
+            |i| i.lines().map(|l| l.parse::<u64>().unwrap()).sum(),
+            &[1, 10],
+            5,
+            1,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "mismatch")]
+    fn reports_mismatch() {
+        compare(numbers, sum, |i| i.lines().count() as u64, &[10], 5, 1);
+    }
+}
@@ -0,0 +1,20 @@
+/// 64 bit FNV-1a. Not cryptographic, but stable across platforms and rust versions, unlike `DefaultHasher`.
+/// Used wherever a hash is stored or must look the same on every run, e.g. the secrets manifest or the colours of a
+/// flamegraph.
+#[must_use]
+pub fn fnv1a(content: &[u8]) -> u64 {
+    content.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::fnv1a;
+
+    #[test]
+    fn hashes_with_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
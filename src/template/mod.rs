@@ -1,48 +1,162 @@
 use crate::Day;
-use std::{env, fs};
+use std::{env, fs, io};
 
 pub mod aoc_cli;
+pub mod bench_env;
+pub mod cache;
 pub mod commands;
+pub mod config;
+pub mod flamegraph;
+pub mod fuzz;
+pub mod generate;
+pub mod hash;
+pub mod ocr;
+pub mod options;
 pub mod readme_benchmarks;
+pub mod report;
 pub mod runner;
+pub mod sampler;
+pub mod secrets;
+pub mod snapshot;
+pub mod viz;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
+pub const ANSI_CLEAR: &str = "\x1b[2J\x1b[H";
 
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    read_data_file(folder, &format!("{day}.txt")).unwrap_or_else(|e| panic!("{e}"))
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    read_data_file(folder, &format!("{day}-{part}.txt")).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Reads a file of `data/`, or of the store for inputs and puzzles. See [`secrets`].
+fn read_data_file(folder: &str, file: &str) -> io::Result<String> {
+    let filepath = env::current_dir()?.join(secrets::path(folder, file));
+    let content = fs::read_to_string(&filepath).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("could not open {}: {e}", filepath.display()),
+        )
+    })?;
+    secrets::verify(folder, file, &content).map_err(io::Error::other)?;
+    Ok(content)
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
+///
+/// Additional functions taking the puzzle input can be registered as fuzz targets,
+/// e.g. `solution!(7, fuzz = [parse, parse_joker])`. `part_one` and `part_two` are always fuzzed.
+/// A random input generator can be registered with `generate = <fn>`, see [`crate::template::generate::Generator`].
+/// A debug explanation for `--explain <value>` can be registered with `explain = <fn>`, see [`crate::template::runner::Explain`].
+/// Arguments of the solution's own can be read once before it runs with `configure = <fn>`, see [`crate::template::runner::Configure`].
+/// Any other argument is rejected.
+/// Alternative implementations of a part can be registered with `variants = [<part> => <fn>]`, e.g.
+/// `variants = [2 => part_two_naive]`, and compared with `--variants`, see [`crate::template::runner::run_variants`].
+/// Command-line options are parsed into [`crate::template::options::RunOptions`], see [`crate::template::options::USAGE`].
+///
+/// The optional arguments can be left out, but the ones that are given must be in the order above:
+/// `solution!(<day>, fuzz = [..], generate = <fn>, explain = <fn>, configure = <fn>, variants = [..])`.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
+    ($day:expr $(, fuzz = [$($target:path),* $(,)?])? $(, generate = $generator:path)? $(, explain = $explain:path)? $(, configure = $configure:path)? $(, variants = [$($part:literal => $variant:path),* $(,)?])?) => {
         /// The current day.
         const DAY: advent_of_code::Day = advent_of_code::day!($day);
 
         fn main() {
+            use advent_of_code::template::generate::{self, Generator};
+            use advent_of_code::template::options::{Mode, RunOptions, USAGE};
             use advent_of_code::template::runner::*;
-            let input = advent_of_code::template::read_file("inputs", DAY);
-            run_part(part_one, &input, DAY, 1);
-            run_part(part_two, &input, DAY, 2);
+
+            let options = RunOptions::from_env().unwrap_or_else(|e| {
+                eprintln!("{e}\n\n{USAGE}");
+                std::process::exit(2);
+            });
+
+            let configure: Option<Configure> = None $(.or(Some($configure as Configure)))?;
+            if let Err(e) = run_configure(configure, &options) {
+                eprintln!("{e}\n\n{USAGE}");
+                std::process::exit(2);
+            }
+
+            let generator: Option<Generator> = None $(.or(Some($generator as Generator)))?;
+            let explain: Option<Explain> = None $(.or(Some($explain as Explain)))?;
+            let parts = [
+                Variant {
+                    part: 1,
+                    name: "part_one",
+                    func: |input| part_one(input).map(|r| r.to_string()),
+                },
+                Variant {
+                    part: 2,
+                    name: "part_two",
+                    func: |input| part_two(input).map(|r| r.to_string()),
+                },
+            ];
+            let variants: &[Variant] = &[$($(Variant {
+                part: $part,
+                name: stringify!($variant),
+                func: |input| $variant(input).map(|r| r.to_string()),
+            },)*)?];
+
+            let result = match &options.mode {
+                Mode::Solve => run(part_one, part_two, DAY, &options),
+                Mode::Explain(value) => match explain {
+                    Some(explain) => run_explain(explain, DAY, value, &options.input),
+                    None => unsupported(DAY, "an explanation"),
+                },
+                Mode::Scale { max_size } => match generator {
+                    Some(generator) => {
+                        run_scaling(part_one, part_two, generator, *max_size);
+                        Ok(())
+                    }
+                    None => unsupported(DAY, "an input generator"),
+                },
+                Mode::Generate { size, seed } => match generator {
+                    Some(generator) => {
+                        generate::run(generator, *size, *seed);
+                        Ok(())
+                    }
+                    None => unsupported(DAY, "an input generator"),
+                },
+                Mode::Profile { .. } => run_profile(part_one, part_two, DAY, &options),
+                Mode::Variants => run_variants(parts, variants, DAY, &options),
+                Mode::Fuzz(fuzz) => {
+                    advent_of_code::template::fuzz::run(
+                        DAY,
+                        &[
+                            ("part_one", |input: &str| {
+                                let _ = part_one(input);
+                            }),
+                            ("part_two", |input: &str| {
+                                let _ = part_two(input);
+                            }),
+                            $($((stringify!($target), |input: &str| {
+                                let _ = $target(input);
+                            }),)*)?
+                        ],
+                        *fuzz,
+                    );
+                    Ok(())
+                }
+            };
+
+            if let Err(e) = result {
+                eprintln!("Could not read input: {e}");
+                std::process::exit(1);
+            }
         }
     };
+    ($($args:tt)*) => {
+        compile_error!(
+            "expected `solution!(<day>, fuzz = [..], generate = <fn>, explain = <fn>, configure = <fn>, variants = [..])`, optional arguments must be given in this order"
+        );
+    };
 }
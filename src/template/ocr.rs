@@ -0,0 +1,428 @@
+/// Reads the block letters that some puzzles draw on a grid, so parts can return an answer that can be submitted.
+/// Lit pixels are `#` or `█`, anything else is dark. Letters are separated by dark columns, or touch on the puzzles'
+/// 5 column pitch, and are drawn in one of the two fonts used by the puzzles, see [`Font`].
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+/// The letters known to appear in puzzles, each drawn as 6 rows of `#` and `.`.
+const SMALL: [(char, &str); 18] = [
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// The letters known to appear in puzzles, each drawn as 10 rows of `#` and `.`.
+const LARGE: [(char, &str); 15] = [
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// The fonts puzzles draw letters in. The font of some art is determined by its height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    /// 4x6 pixels, used by most puzzles.
+    Small,
+    /// 6x10 pixels, e.g. in 2018 day 10.
+    Large,
+}
+
+impl Font {
+    #[must_use]
+    pub fn height(self) -> usize {
+        match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        }
+    }
+
+    fn for_height(height: usize) -> Option<Self> {
+        [Font::Small, Font::Large]
+            .into_iter()
+            .find(|font| font.height() == height)
+    }
+
+    fn letters(self) -> &'static [(char, &'static str)] {
+        match self {
+            Font::Small => &SMALL,
+            Font::Large => &LARGE,
+        }
+    }
+
+    fn glyphs(self) -> HashMap<Glyph, char> {
+        self.letters()
+            .iter()
+            .map(|(letter, art)| (columns(&parse_rows(art)), *letter))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// Art without lit pixels.
+    Empty,
+    /// The art is not as high as the letters of any font.
+    UnknownHeight(usize),
+    /// A glyph that is not a letter of the font, with the column it starts at and its pixels.
+    UnknownGlyph { column: usize, glyph: String },
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Empty => write!(f, "no lit pixels to read letters from"),
+            OcrError::UnknownHeight(height) => write!(
+                f,
+                "letters are {} or {} pixels high, the art is {height}",
+                Font::Small.height(),
+                Font::Large.height()
+            ),
+            OcrError::UnknownGlyph { column, glyph } => {
+                write!(f, "unknown letter at column {column}:\n{glyph}")
+            }
+        }
+    }
+}
+
+fn is_lit(c: char) -> bool {
+    matches!(c, '#' | '█')
+}
+
+fn parse_rows(art: &str) -> Vec<Vec<bool>> {
+    art.lines()
+        .map(|line| line.chars().map(is_lit).collect())
+        .collect()
+}
+
+/// Columns of lit pixels, one `Vec` per column, top to bottom.
+type Glyph = Vec<Vec<bool>>;
+
+fn columns<R: AsRef<[bool]>>(rows: &[R]) -> Glyph {
+    let width = rows.iter().map(|row| row.as_ref().len()).max().unwrap_or(0);
+    (0..width)
+        .map(|x| {
+            rows.iter()
+                .map(|row| row.as_ref().get(x) == Some(&true))
+                .collect()
+        })
+        .collect()
+}
+
+fn render(glyph: &Glyph) -> String {
+    let height = glyph.first().map_or(0, Vec::len);
+    (0..height)
+        .map(|y| {
+            glyph
+                .iter()
+                .map(|column| if column[y] { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Reads the letters in a run of lit columns. Letters are usually separated by a dark column, but puzzles draw them
+/// on a 5 column pitch, so a 5 pixel wide `Y` touches the next letter. Wider letters are tried first.
+fn split_run(run: &[Vec<bool>], glyphs: &HashMap<Glyph, char>, widths: &[usize]) -> Option<String> {
+    if run.is_empty() {
+        return Some(String::new());
+    }
+    widths
+        .iter()
+        .filter(|&&width| width <= run.len())
+        .find_map(|&width| {
+            let letter = glyphs.get(&run[..width])?;
+            let rest = split_run(&run[width..], glyphs, widths)?;
+            Some(format!("{letter}{rest}"))
+        })
+}
+
+/// Returns the letters drawn in a grid of pixels, e.g. a `Vec<Vec<bool>>` or `[[bool; 40]; 6]`.
+/// Empty rows around the letters are ignored.
+pub fn recognize_grid<R: AsRef<[bool]>>(rows: &[R]) -> Result<String, OcrError> {
+    let is_empty = |row: &R| !row.as_ref().contains(&true);
+    let top = rows
+        .iter()
+        .position(|row| !is_empty(row))
+        .ok_or(OcrError::Empty)?;
+    let bottom = rows.iter().rposition(|row| !is_empty(row)).unwrap_or(top);
+    let rows = &rows[top..=bottom];
+
+    let font = Font::for_height(rows.len()).ok_or(OcrError::UnknownHeight(rows.len()))?;
+    let glyphs = font.glyphs();
+    let mut widths: Vec<usize> = glyphs.keys().map(Vec::len).collect();
+    widths.sort_unstable_by(|a, b| b.cmp(a));
+    widths.dedup();
+
+    let mut letters = String::new();
+    let mut start = None;
+    let columns = columns(rows);
+
+    // a dark column after the last one ends the last glyph.
+    for (x, column) in columns.iter().chain([&vec![false]]).enumerate() {
+        match (start, column.contains(&true)) {
+            (None, true) => start = Some(x),
+            (Some(from), false) => {
+                let run = &columns[from..x];
+                match split_run(run, &glyphs, &widths) {
+                    Some(run) => letters.push_str(&run),
+                    None => {
+                        return Err(OcrError::UnknownGlyph {
+                            column: from,
+                            glyph: render(&run.to_vec()),
+                        })
+                    }
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(letters)
+}
+
+/// Returns the letters drawn in `art`, rows of `#` and `.` separated by newlines.
+pub fn recognize(art: &str) -> Result<String, OcrError> {
+    recognize_grid(&parse_rows(art))
+}
+
+/// Returns the letters drawn by lit `(x, y)` coordinates, which may be negative or offset, e.g. positions of
+/// particles or dots after folding paper.
+pub fn recognize_points(points: impl IntoIterator<Item = (i64, i64)>) -> Result<String, OcrError> {
+    let points: Vec<_> = points.into_iter().collect();
+    let min_x = points.iter().map(|p| p.0).min().ok_or(OcrError::Empty)?;
+    let min_y = points.iter().map(|p| p.1).min().ok_or(OcrError::Empty)?;
+    let max_x = points.iter().map(|p| p.0).max().ok_or(OcrError::Empty)?;
+    let max_y = points.iter().map(|p| p.1).max().ok_or(OcrError::Empty)?;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let mut rows = vec![vec![false; (max_x - min_x + 1) as usize]; (max_y - min_y + 1) as usize];
+    for (x, y) in points {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (x, y) = ((x - min_x) as usize, (y - min_y) as usize);
+        rows[y][x] = true;
+    }
+
+    recognize_grid(&rows)
+}
+
+/// Like [`recognize`], but `None` if the art cannot be read.
+#[must_use]
+pub fn read(art: &str) -> Option<String> {
+    recognize(art).ok()
+}
+
+/// The letters drawn in `art` if they can be read, the art itself otherwise. Parts can return this to print a
+/// submittable answer when possible, and the art to read it yourself when not.
+#[must_use]
+pub fn letters_or_art(art: &str) -> String {
+    read(art).unwrap_or_else(|| art.to_string())
+}
+
+/// An answer drawn in block letters. Displays as the letters, so a part can return it and it is submitted as usual;
+/// the alternate form (`{:#}`) shows the art above them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Letters {
+    pub letters: String,
+    pub art: String,
+}
+
+impl Letters {
+    pub fn new(art: impl Into<String>) -> Result<Self, OcrError> {
+        let art = art.into();
+        Ok(Self {
+            letters: recognize(&art)?,
+            art,
+        })
+    }
+}
+
+impl Display for Letters {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            writeln!(f, "{}", self.art.trim_end())?;
+        }
+        write!(f, "{}", self.letters)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        letters_or_art, read, recognize, recognize_grid, recognize_points, Font, Letters, OcrError,
+    };
+
+    /// Draws all letters of `font` next to each other, separated by a dark column.
+    fn alphabet(font: Font) -> (String, String) {
+        let letters = font.letters();
+        let rows = (0..font.height())
+            .map(|y| {
+                letters
+                    .iter()
+                    .map(|(_, art)| art.lines().nth(y).unwrap())
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        (letters.iter().map(|(letter, _)| letter).collect(), rows)
+    }
+
+    #[test]
+    fn reads_letters() {
+        let art = "\
+#..#.####.###..###..
+#..#.#....#..#.#..#.
+####.###..###..#..#.
+#..#.#....#..#.###..
+#..#.#....#..#.#....
+#..#.####.###..#....";
+        assert_eq!(read(art), Some("HEBP".into()));
+        assert_eq!(
+            read(&format!("\n{}\n\n", art.replace('.', " "))),
+            Some("HEBP".into())
+        );
+
+        for font in [Font::Small, Font::Large] {
+            let (letters, art) = alphabet(font);
+            assert_eq!(recognize(&art), Ok(letters));
+        }
+    }
+
+    #[test]
+    fn reads_touching_letters() {
+        // a `Y` on the 5 column pitch has no dark column before the next letter.
+        let art = "\
+#...#.##..#...#
+#...##..#.#...#
+.#.#.#..#..#.#.
+..#..####...#..
+..#..#..#...#..
+..#..#..#...#..";
+        assert_eq!(recognize(art), Ok("YAY".into()));
+        assert!(matches!(
+            recognize(&art.replace("..#..####", "..#.#####")),
+            Err(OcrError::UnknownGlyph { column: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn reads_grids_and_points() {
+        let hi = [
+            [true, false, false, true, false, true, true, true],
+            [true, false, false, true, false, false, true, false],
+            [true, true, true, true, false, false, true, false],
+            [true, false, false, true, false, false, true, false],
+            [true, false, false, true, false, false, true, false],
+            [true, false, false, true, false, true, true, true],
+        ];
+        assert_eq!(recognize_grid(&hi), Ok("HI".into()));
+
+        let points = hi.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &lit)| lit)
+                .map(move |(x, _)| (x as i64 - 20, y as i64 + 7))
+        });
+        assert_eq!(recognize_points(points), Ok("HI".into()));
+    }
+
+    #[test]
+    fn rejects_unknown_art() {
+        assert_eq!(read("42"), None);
+        assert_eq!(recognize(""), Err(OcrError::Empty));
+        assert_eq!(recognize("#\n#\n#"), Err(OcrError::UnknownHeight(3)));
+        assert_eq!(
+            recognize("####.#\n#....#\n###..#\n#....#\n#....#\n####.#"),
+            Err(OcrError::UnknownGlyph {
+                column: 5,
+                glyph: "#\n#\n#\n#\n#\n#".into()
+            })
+        );
+        assert_eq!(letters_or_art("#\n#"), "#\n#");
+    }
+
+    #[test]
+    fn displays_letters() {
+        let art = "####\n...#\n..#.\n.#..\n#...\n####";
+        let letters = Letters::new(art).unwrap();
+        assert_eq!(letters.to_string(), "Z");
+        assert_eq!(format!("{letters:#}"), format!("{art}\nZ"));
+        assert!(Letters::new("#").is_err());
+    }
+}
@@ -0,0 +1,473 @@
+/// Options of a solution binary, parsed once by `solution!` and passed to the runner.
+/// `cargo solve` parses the same options, so flags are validated before a solution is even built.
+use std::{
+    ffi::OsString,
+    fmt::{self, Display, Formatter},
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use pico_args::Arguments;
+
+use super::{fuzz, read_data_file};
+use crate::Day;
+
+pub const USAGE: &str = "\
+Usage: cargo solve <day> [options]
+       cargo run --bin <day> -- [options]
+
+Options:
+  --part <1|2>             only run one part
+  --example                run on the example instead of the puzzle input
+  --input <path>           read the input from a file, `-` reads from stdin
+  --format <human|json>    print results for humans (default) or as one JSON object per part
+  --time                   bench each part
+  --submit <1|2>           submit the answer of a part via aoc-cli
+
+Modes, instead of solving:
+  --explain <value>        explain the result for a value, see `solution!`
+  --scale [--max-size <n>] estimate the complexity of both parts with generated inputs
+  --generate <size>        print a generated input, reproducible with `--seed <n>`
+  --fuzz                   fuzz the parsers, tuned with `--iterations <n>`, `--seed <n>` and `--timeout <ms>`
+  --profile                bench the selected parts in a loop for a profiler, `--samples <path>` writes the stacks
+                           sampled by the built-in one to a file, see `cargo profile`
+  --variants               bench the variants of the selected parts and check that they agree
+
+Other arguments are left to the solution, see `configure` of `solution!`. Arguments it does not read are rejected.";
+
+/// Where the puzzle input is read from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Input {
+    /// The downloaded puzzle input.
+    #[default]
+    Puzzle,
+    /// The example of `data/examples`.
+    Example,
+    File(PathBuf),
+    Stdin,
+}
+
+impl Input {
+    pub fn read(&self, day: Day) -> io::Result<String> {
+        match self {
+            Input::Puzzle => read_data_file("inputs", &format!("{day}.txt")),
+            Input::Example => read_data_file("examples", &format!("{day}.txt")),
+            Input::File(path) => fs::read_to_string(path).map_err(|e| {
+                io::Error::new(e.kind(), format!("could not open {}: {e}", path.display()))
+            }),
+            Input::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Human,
+    /// One JSON object per part, e.g. for scripts comparing solutions.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format `{s}`, expected `human` or `json`")),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Human => write!(f, "human"),
+            Format::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// What a solution binary does when it is invoked.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Mode {
+    #[default]
+    Solve,
+    Explain(String),
+    Scale {
+        max_size: Option<usize>,
+    },
+    Generate {
+        size: usize,
+        seed: Option<u64>,
+    },
+    Fuzz(fuzz::Options),
+    Profile {
+        /// Where the built-in sampler writes folded stacks. Without it, an external profiler is expected.
+        samples: Option<PathBuf>,
+    },
+    Variants,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunOptions {
+    pub mode: Mode,
+    pub input: Input,
+    pub format: Format,
+    /// Only run this part. Both parts run if not set.
+    pub part: Option<u8>,
+    pub time: bool,
+    pub submit: Option<u8>,
+    /// Arguments the template does not know about, left for the solution.
+    pub extra: Vec<String>,
+}
+
+/// Arguments of a solution's own, e.g. `--bag` on day 2, taken from [`RunOptions::extra`] with the same parser as
+/// the template's options. Read by the `configure` hook of `solution!`, see [`crate::template::runner::Configure`].
+pub struct ExtraArgs(Arguments);
+
+impl ExtraArgs {
+    /// Takes the value of an option like `--bag <cubes>`.
+    pub fn value<T: FromStr>(&mut self, flag: &'static str) -> Result<Option<T>, String>
+    where
+        T::Err: Display,
+    {
+        self.0.opt_value_from_str(flag).map_err(|e| e.to_string())
+    }
+
+    /// Fails with the arguments that were not taken, so a typo like `--tme` is not silently ignored.
+    pub fn finish(self) -> Result<(), String> {
+        let unknown: Vec<String> = self
+            .0
+            .finish()
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("unknown argument(s): {}", unknown.join(" ")))
+        }
+    }
+}
+
+/// Parses the value of a flag that selects a part, e.g. `--part`.
+pub fn parse_part(flag: &str, s: &str) -> Result<u8, String> {
+    match s {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("`{flag}` expects part 1 or 2, found `{s}`")),
+    }
+}
+
+impl RunOptions {
+    /// Parses the options of a solution binary.
+    pub fn parse(args: Vec<String>) -> Result<Self, String> {
+        let mut args = Arguments::from_vec(args.into_iter().map(OsString::from).collect());
+        let mut options = Self::parse_known(&mut args)?;
+        options.extra = args
+            .finish()
+            .into_iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        Ok(options)
+    }
+
+    /// Parses the options the template knows about and leaves all other arguments in `args`.
+    pub fn parse_known(args: &mut Arguments) -> Result<Self, String> {
+        let err = |e: pico_args::Error| e.to_string();
+
+        let part = args
+            .opt_value_from_str::<_, String>("--part")
+            .map_err(err)?
+            .map(|s| parse_part("--part", &s))
+            .transpose()?;
+        let submit = args
+            .opt_value_from_str::<_, String>("--submit")
+            .map_err(err)?
+            .map(|s| parse_part("--submit", &s))
+            .transpose()?;
+        let example = args.contains("--example");
+        let file: Option<String> = args.opt_value_from_str("--input").map_err(err)?;
+        let format = args
+            .opt_value_from_str("--format")
+            .map_err(err)?
+            .unwrap_or_default();
+        let time = args.contains("--time");
+
+        let explain: Option<String> = args.opt_value_from_str("--explain").map_err(err)?;
+        let scale = args.contains("--scale");
+        let max_size: Option<usize> = args.opt_value_from_str("--max-size").map_err(err)?;
+        let generate: Option<usize> = args.opt_value_from_str("--generate").map_err(err)?;
+        let fuzz = args.contains("--fuzz");
+        let profile = args.contains("--profile");
+        let samples: Option<PathBuf> = args.opt_value_from_str("--samples").map_err(err)?;
+        let variants = args.contains("--variants");
+        let seed: Option<u64> = args.opt_value_from_str("--seed").map_err(err)?;
+        let iterations: Option<usize> = args.opt_value_from_str("--iterations").map_err(err)?;
+        let timeout: Option<u64> = args.opt_value_from_str("--timeout").map_err(err)?;
+
+        let modes = [
+            explain.is_some(),
+            scale,
+            generate.is_some(),
+            fuzz,
+            profile,
+            variants,
+        ];
+        if modes.iter().filter(|&&m| m).count() > 1 {
+            return Err(
+                "only one of `--explain`, `--scale`, `--generate`, `--fuzz`, `--profile` and `--variants` can be used at a time"
+                    .into(),
+            );
+        }
+        if max_size.is_some() && !scale {
+            return Err("`--max-size` requires `--scale`".into());
+        }
+        if seed.is_some() && generate.is_none() && !fuzz {
+            return Err("`--seed` requires `--generate` or `--fuzz`".into());
+        }
+        if samples.is_some() && !profile {
+            return Err("`--samples` requires `--profile`".into());
+        }
+        if (iterations.is_some() || timeout.is_some()) && !fuzz {
+            return Err("`--iterations` and `--timeout` require `--fuzz`".into());
+        }
+        if let (Some(part), Some(submit)) = (part, submit) {
+            if part != submit {
+                return Err(format!(
+                    "cannot submit part {submit} when only running part {part}"
+                ));
+            }
+        }
+
+        let input = match (example, file) {
+            (true, Some(_)) => return Err("`--example` and `--input` cannot be combined".into()),
+            (true, None) => Input::Example,
+            (false, Some(path)) if path == "-" => Input::Stdin,
+            (false, Some(path)) => Input::File(path.into()),
+            (false, None) => Input::Puzzle,
+        };
+
+        let mode = if let Some(value) = explain {
+            Mode::Explain(value)
+        } else if scale {
+            Mode::Scale { max_size }
+        } else if let Some(size) = generate {
+            Mode::Generate { size, seed }
+        } else if fuzz {
+            Mode::Fuzz(fuzz::Options::new(iterations, seed, timeout))
+        } else if profile {
+            Mode::Profile { samples }
+        } else if variants {
+            Mode::Variants
+        } else {
+            Mode::Solve
+        };
+
+        Ok(Self {
+            mode,
+            input,
+            format,
+            part,
+            time,
+            submit,
+            extra: vec![],
+        })
+    }
+
+    /// The arguments left for the solution, see [`ExtraArgs`].
+    #[must_use]
+    pub fn extra_args(&self) -> ExtraArgs {
+        ExtraArgs(Arguments::from_vec(
+            self.extra.iter().map(OsString::from).collect(),
+        ))
+    }
+
+    /// Reads the input of each selected part, the input of a part that is not run is empty. Parts share one input,
+    /// except for examples that only exist per part, like `01-1.txt` and `01-2.txt`.
+    pub fn read_inputs(&self, day: Day) -> io::Result<[String; 2]> {
+        match self.input.read(day) {
+            Ok(input) => Ok([input.clone(), input]),
+            Err(e) if self.input == Input::Example && e.kind() == io::ErrorKind::NotFound => {
+                let read = |part: u8| match self.runs(part) {
+                    true => read_data_file("examples", &format!("{day}-{part}.txt")),
+                    false => Ok(String::new()),
+                };
+                Ok([read(1)?, read(2)?])
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses the arguments the binary was invoked with.
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1).collect())
+    }
+
+    /// Whether `part` should be run.
+    #[must_use]
+    pub fn runs(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+
+    /// Turns the options back into arguments, to pass them on to a solution binary.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = vec![];
+        let mut push = |flag: &str, value: Option<String>| {
+            args.push(flag.to_string());
+            args.extend(value);
+        };
+
+        if let Some(part) = self.part {
+            push("--part", Some(part.to_string()));
+        }
+        match &self.input {
+            Input::Puzzle => {}
+            Input::Example => push("--example", None),
+            Input::File(path) => push("--input", Some(path.to_string_lossy().to_string())),
+            Input::Stdin => push("--input", Some("-".to_string())),
+        }
+        if self.format != Format::Human {
+            push("--format", Some(self.format.to_string()));
+        }
+        if self.time {
+            push("--time", None);
+        }
+        if let Some(submit) = self.submit {
+            push("--submit", Some(submit.to_string()));
+        }
+
+        match &self.mode {
+            Mode::Solve => {}
+            Mode::Explain(value) => push("--explain", Some(value.clone())),
+            Mode::Scale { max_size } => {
+                push("--scale", None);
+                if let Some(max_size) = max_size {
+                    push("--max-size", Some(max_size.to_string()));
+                }
+            }
+            Mode::Generate { size, seed } => {
+                push("--generate", Some(size.to_string()));
+                if let Some(seed) = seed {
+                    push("--seed", Some(seed.to_string()));
+                }
+            }
+            Mode::Fuzz(options) => {
+                push("--fuzz", None);
+                push("--iterations", Some(options.iterations.to_string()));
+                push("--seed", Some(options.seed.to_string()));
+                push("--timeout", Some(options.timeout.as_millis().to_string()));
+            }
+            Mode::Profile { samples } => {
+                push("--profile", None);
+                if let Some(samples) = samples {
+                    push("--samples", Some(samples.to_string_lossy().to_string()));
+                }
+            }
+            Mode::Variants => push("--variants", None),
+        }
+
+        args.extend(self.extra.iter().cloned());
+        args
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Format, Input, Mode, RunOptions};
+
+    fn parse(args: &str) -> Result<RunOptions, String> {
+        RunOptions::parse(args.split_whitespace().map(String::from).collect())
+    }
+
+    #[test]
+    fn parses_options() {
+        let options = parse("--part 2 --input - --format json --time --bag 1").unwrap();
+        assert_eq!(options.part, Some(2));
+        assert_eq!(options.input, Input::Stdin);
+        assert_eq!(options.format, Format::Json);
+        assert!(options.time);
+        assert!(!options.runs(1));
+        assert_eq!(options.extra, vec!["--bag", "1"]);
+        assert_eq!(parse("").unwrap(), RunOptions::default());
+        assert_eq!(
+            parse("--scale --max-size 64").unwrap().mode,
+            Mode::Scale { max_size: Some(64) }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        assert_eq!(
+            parse("--part 3").unwrap_err(),
+            "`--part` expects part 1 or 2, found `3`"
+        );
+        assert!(parse("--fuzz --scale").is_err());
+        assert!(parse("--profile --explain 1").is_err());
+        assert!(parse("--max-size 8").is_err());
+        assert!(parse("--samples x.folded").is_err());
+        assert!(parse("--example --input x.txt").is_err());
+        assert!(parse("--part 1 --submit 2").is_err());
+        assert!(parse("--submit").is_err());
+    }
+
+    #[test]
+    fn rejects_unread_extra_args() {
+        let options = parse("--time --bag 1 --tme").unwrap();
+        let mut args = options.extra_args();
+        assert_eq!(args.value::<u32>("--bag"), Ok(Some(1)));
+        assert_eq!(args.finish().unwrap_err(), "unknown argument(s): --tme");
+        assert_eq!(parse("--time").unwrap().extra_args().finish(), Ok(()));
+    }
+
+    #[test]
+    fn reads_inputs_per_part() {
+        // day 1 only has an example per part.
+        let day = crate::day!(1);
+        let [one, two] = parse("--example").unwrap().read_inputs(day).unwrap();
+        assert!(!one.is_empty() && !two.is_empty() && one != two);
+        let [one, two] = parse("--example --part 2")
+            .unwrap()
+            .read_inputs(day)
+            .unwrap();
+        assert!(one.is_empty() && !two.is_empty());
+
+        let [one, two] = parse("--example")
+            .unwrap()
+            .read_inputs(crate::day!(3))
+            .unwrap();
+        assert_eq!(one, two);
+        assert_eq!(
+            parse("--input missing.txt")
+                .unwrap()
+                .read_inputs(day)
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn round_trips_arguments() {
+        for args in [
+            "--part 1 --example --time --submit 1",
+            "--input x.txt --format json --generate 10 --seed 3",
+            "--fuzz --iterations 5 --seed 1 --timeout 20 --bag 1",
+            "--profile --part 2 --example",
+            "--profile --samples x.folded",
+            "--variants --input x.txt",
+        ] {
+            let options = parse(args).unwrap();
+            assert_eq!(RunOptions::parse(options.to_args()).unwrap(), options);
+        }
+    }
+}
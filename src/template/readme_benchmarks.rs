@@ -2,6 +2,7 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
+use crate::template::config;
 use crate::Day;
 
 static MARKER: &str = "<!--- benchmarking table --->";
@@ -24,6 +25,8 @@ pub struct Timings {
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
+    /// Summary of the environment the timings were taken in, see [`Environment::summary`].
+    pub environment: String,
 }
 
 pub struct TablePosition {
@@ -33,7 +36,7 @@ pub struct TablePosition {
 
 #[must_use]
 pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+    format!("./{}", config::get().bin_path(day).display())
 }
 
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
@@ -65,18 +68,19 @@ fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> St
         MARKER.into(),
         header,
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+        "| Day | Part 1 | Part 2 | Environment |".into(),
+        "| :---: | :---: | :---:  | :--- |".into(),
     ];
 
     for timing in timings {
         let path = get_path_for_bin(timing.day);
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}]({}) | `{}` | `{}` | {} |",
             timing.day.into_inner(),
             path,
             timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            timing.part_2.unwrap_or_else(|| "-".into()),
+            timing.environment
         ));
     }
 
@@ -94,8 +98,9 @@ fn update_content(s: &mut String, timings: Vec<Timings>, total_millis: f64) -> R
     Ok(())
 }
 
+/// Writes the benchmark table to the readme, every day together with the environment its timings were taken in.
 pub fn update(timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
-    let path = "README.md";
+    let path = &config::get().paths.readme;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
     update_content(&mut readme, timings, total_millis)?;
     fs::write(path, &readme)?;
@@ -107,6 +112,8 @@ mod tests {
     use super::{update_content, Timings, MARKER};
     use crate::day;
 
+    const ENVIRONMENT: &str = "Ryzen (16 threads), rustc 1.74.0, opt-level 3";
+
     fn get_mock_timings() -> Vec<Timings> {
         vec![
             Timings {
@@ -114,18 +121,21 @@ mod tests {
                 part_1: Some("10ms".into()),
                 part_2: Some("20ms".into()),
                 total_nanos: 3e+10,
+                environment: ENVIRONMENT.into(),
             },
             Timings {
                 day: day!(2),
                 part_1: Some("30ms".into()),
                 part_2: Some("40ms".into()),
                 total_nanos: 7e+10,
+                environment: ENVIRONMENT.into(),
             },
             Timings {
                 day: day!(4),
                 part_1: Some("40ms".into()),
                 part_2: Some("50ms".into()),
                 total_nanos: 9e+10,
+                environment: "Ryzen (16 threads), rustc 1.75.0, opt-level 3".into(),
             },
         ]
     }
@@ -170,11 +180,11 @@ mod tests {
             "<!--- benchmarking table --->",
             "## Benchmarks",
             "",
-            "| Day | Part 1 | Part 2 |",
-            "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| Day | Part 1 | Part 2 | Environment |",
+            "| :---: | :---: | :---:  | :--- |",
+            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | Ryzen (16 threads), rustc 1.74.0, opt-level 3 |",
+            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | Ryzen (16 threads), rustc 1.74.0, opt-level 3 |",
+            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` | Ryzen (16 threads), rustc 1.75.0, opt-level 3 |",
             "",
             "**Total: 190.00ms**",
             "<!--- benchmarking table --->",
@@ -0,0 +1,221 @@
+/// Results of running solution parts, see [`crate::template::runner::solve_part`].
+/// Reports are printed for humans or as one JSON object per line. The JSON form is how the `all` and `dashboard`
+/// commands receive results from the solution binaries they spawn.
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+use crate::Day;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Solved,
+    /// The part returned `None`.
+    Unsolved,
+    /// The part was not selected to run.
+    Skipped,
+}
+
+/// Execution times of a part. Without benching, this holds the single run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: u128,
+    pub mean: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    #[must_use]
+    pub fn from_samples(durations: &[Duration]) -> Self {
+        let total: u128 = durations.iter().map(Duration::as_nanos).sum();
+        let samples = durations.len() as u128;
+        #[allow(clippy::cast_possible_truncation)]
+        let mean = Duration::from_nanos((total / samples.max(1)) as u64);
+
+        Self {
+            samples,
+            mean,
+            min: durations.iter().copied().min().unwrap_or_default(),
+            max: durations.iter().copied().max().unwrap_or_default(),
+        }
+    }
+
+    /// Whether the part was benched rather than run once.
+    #[must_use]
+    pub fn is_benched(&self) -> bool {
+        self.samples > 1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunReport {
+    pub day: Day,
+    pub part: u8,
+    pub status: Status,
+    pub answer: Option<String>,
+    /// `None` for skipped parts.
+    pub stats: Option<Stats>,
+}
+
+impl RunReport {
+    #[must_use]
+    pub fn skipped(day: Day, part: u8) -> Self {
+        Self {
+            day,
+            part,
+            status: Status::Skipped,
+            answer: None,
+            stats: None,
+        }
+    }
+
+    /// Formats the report the way `cargo solve` prints it, e.g. `Part 1: 42 (1.2ms @ 10 samples)`.
+    #[must_use]
+    pub fn to_human(&self) -> String {
+        let part = format!("Part {}", self.part);
+        let duration = self.stats.map_or(String::new(), |stats| {
+            if stats.is_benched() {
+                format!(" ({:.1?} @ {} samples)", stats.mean, stats.samples)
+            } else {
+                format!(" ({:.1?})", stats.mean)
+            }
+        });
+
+        match &self.answer {
+            Some(answer) if answer.contains('\n') => format!("{part}: ▼ {duration}\n{answer}"),
+            Some(answer) => format!("{part}: {ANSI_BOLD}{answer}{ANSI_RESET}{duration}"),
+            None => format!("{part}: ✖             "),
+        }
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let json = JsonReport {
+            day: self.day.into_inner(),
+            part: self.part,
+            status: self.status,
+            answer: self.answer.clone(),
+            stats: self.stats.map(|stats| JsonStats {
+                samples: stats.samples as u64,
+                mean_ns: stats.mean.as_nanos() as u64,
+                min_ns: stats.min.as_nanos() as u64,
+                max_ns: stats.max.as_nanos() as u64,
+            }),
+        };
+        serde_json::to_string(&json).unwrap()
+    }
+
+    /// Parses a line printed by [`RunReport::to_json`]. Returns `None` for any other line, e.g. debug output.
+    #[must_use]
+    pub fn from_json(line: &str) -> Option<Self> {
+        let json: JsonReport = serde_json::from_str(line.trim()).ok()?;
+        Some(Self {
+            day: Day::new(json.day)?,
+            part: json.part,
+            status: json.status,
+            answer: json.answer,
+            stats: json.stats.map(|stats| Stats {
+                samples: u128::from(stats.samples),
+                mean: Duration::from_nanos(stats.mean_ns),
+                min: Duration::from_nanos(stats.min_ns),
+                max: Duration::from_nanos(stats.max_ns),
+            }),
+        })
+    }
+}
+
+/// The JSON form of a [`RunReport`], with durations in nanoseconds.
+#[derive(Serialize, Deserialize)]
+struct JsonReport {
+    day: u8,
+    part: u8,
+    status: Status,
+    answer: Option<String>,
+    #[serde(flatten)]
+    stats: Option<JsonStats>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonStats {
+    samples: u64,
+    mean_ns: u64,
+    min_ns: u64,
+    max_ns: u64,
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{RunReport, Stats, Status};
+    use crate::day;
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    fn report(answer: Option<&str>, samples: u128) -> RunReport {
+        RunReport {
+            day: day!(1),
+            part: 2,
+            status: if answer.is_some() {
+                Status::Solved
+            } else {
+                Status::Unsolved
+            },
+            answer: answer.map(String::from),
+            stats: Some(Stats {
+                samples,
+                mean: Duration::from_micros(1200),
+                min: Duration::from_micros(1000),
+                max: Duration::from_micros(2000),
+            }),
+        }
+    }
+
+    #[test]
+    fn computes_stats() {
+        let stats = Stats::from_samples(&[
+            Duration::from_nanos(10),
+            Duration::from_nanos(30),
+            Duration::from_nanos(20),
+        ]);
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.mean, Duration::from_nanos(20));
+        assert_eq!(stats.min, Duration::from_nanos(10));
+        assert_eq!(stats.max, Duration::from_nanos(30));
+    }
+
+    #[test]
+    fn formats_for_humans() {
+        assert_eq!(
+            report(Some("42"), 10).to_human(),
+            format!("Part 2: {ANSI_BOLD}42{ANSI_RESET} (1.2ms @ 10 samples)")
+        );
+        assert_eq!(
+            report(Some("a\nb"), 1).to_human(),
+            "Part 2: ▼  (1.2ms)\na\nb"
+        );
+        assert_eq!(report(None, 1).to_human(), "Part 2: ✖             ");
+    }
+
+    #[test]
+    fn round_trips_json() {
+        for report in [
+            report(Some("42"), 10),
+            // answers can contain anything, including what looks like the format itself.
+            report(Some("\"x\",\\ @ ( ) ms (2s @ 5 samples)\n#.#\u{1}"), 1),
+            report(None, 1),
+            RunReport::skipped(day!(25), 1),
+        ] {
+            assert_eq!(RunReport::from_json(&report.to_json()), Some(report));
+        }
+
+        assert_eq!(
+            report(Some("42"), 10).to_json(),
+            r#"{"day":1,"part":2,"status":"solved","answer":"42","samples":10,"mean_ns":1200000,"min_ns":1000000,"max_ns":2000000}"#
+        );
+        assert_eq!(RunReport::from_json("Part 1: 42 (1.2ms)"), None);
+        assert_eq!(RunReport::from_json("{\"day\":1}"), None);
+    }
+}
@@ -1,67 +1,231 @@
 /// Encapsulates code that interacts with solution functions.
-use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
+/// [`solve`] and [`solve_part`] return [`RunReport`]s without printing anything, so tests and tools can run
+/// solutions programmatically. [`run`] prints these reports and is what `solution!` does by default.
+use crate::template::flamegraph;
+use crate::template::generate::Generator;
+use crate::template::options::{ExtraArgs, Format, Input, Mode, RunOptions};
+use crate::template::sampler::Sampler;
+use crate::template::{aoc_cli, bench_env, config, ANSI_ITALIC, ANSI_RESET};
 use crate::Day;
+use rand::{rngs::StdRng, SeedableRng};
 use std::fmt::Display;
-use std::io::{stdout, Write};
-use std::process::Output;
+use std::fs;
+use std::io::{self, stdout, Write};
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, process};
 
 use super::ANSI_BOLD;
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
-    let part_str = format!("Part {part}");
+pub use super::report::{RunReport, Stats, Status};
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+/// Runs the parts selected by `options` on the input they point to. Parts that are not selected are reported as
+/// [`Status::Skipped`].
+pub fn solve<A: Display, B: Display>(
+    part_one: impl Fn(&str) -> Option<A>,
+    part_two: impl Fn(&str) -> Option<B>,
+    day: Day,
+    options: &RunOptions,
+) -> io::Result<[RunReport; 2]> {
+    let [one, two] = options.read_inputs(day)?;
+
+    let one = if options.runs(1) {
+        solve_part(part_one, one.as_str(), day, 1, options.time)
+    } else {
+        RunReport::skipped(day, 1)
+    };
+    let two = if options.runs(2) {
+        solve_part(part_two, two.as_str(), day, 2, options.time)
+    } else {
+        RunReport::skipped(day, 2)
+    };
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    Ok([one, two])
+}
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+/// Runs a solution part once, or benches it if `time` is set, see [`run_timed`].
+pub fn solve_part<I: Clone, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+    time: bool,
+) -> RunReport {
+    report_part(func, input, day, part, time, |_| {})
+}
+
+/// Like [`solve_part`], but calls `hook` with the answer before benching starts.
+fn report_part<I: Clone, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+    time: bool,
+    hook: impl Fn(Option<&str>),
+) -> RunReport {
+    let (result, stats) = run_timed(func, input, time, |result| {
+        hook(result.as_ref().map(ToString::to_string).as_deref());
+    });
+    let answer = result.map(|r| r.to_string());
+
+    RunReport {
+        day,
+        part,
+        status: if answer.is_some() {
+            Status::Solved
+        } else {
+            Status::Unsolved
+        },
+        answer,
+        stats: Some(stats),
     }
 }
 
-/// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
-///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+/// Runs and prints the parts selected by `options`. This is what `solution!` does by default.
+pub fn run<A: Display, B: Display>(
+    part_one: impl Fn(&str) -> Option<A>,
+    part_two: impl Fn(&str) -> Option<B>,
+    day: Day,
+    options: &RunOptions,
+) -> io::Result<()> {
+    let [one, two] = options.read_inputs(day)?;
+    run_part(part_one, one.as_str(), day, 1, options);
+    run_part(part_two, two.as_str(), day, 2, options);
+    Ok(())
+}
+
+pub fn run_part<I: Clone, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+    options: &RunOptions,
+) {
+    if !options.runs(part) {
+        return;
+    }
+
+    let human = options.format == Format::Human;
+
+    let report = report_part(func, input, day, part, options.time, |answer| {
+        if human {
+            print_intermediate(answer, part);
+            if options.time {
+                print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+            }
+            let _ = stdout().flush();
+        }
+    });
+
+    match options.format {
+        Format::Human => println!("\r{}", report.to_human()),
+        Format::Json => println!("{}", report.to_json()),
+    }
+
+    if let (Some(answer), true) = (&report.answer, options.submit == Some(part)) {
+        submit_result(answer, day, part);
+    }
+}
+
+/// How long `--profile` keeps benching a part, so a profiler attached to the process collects enough samples.
+const PROFILE_DURATION: Duration = Duration::from_secs(5);
+
+/// Entry point used by the `solution!` macro when a solution is invoked with `--profile`.
+/// Benches the selected parts over and over, with the same loop as `--time`, so profiles reflect the benched code.
+/// With `--samples <path>`, the loop is sampled by the built-in [`Sampler`] and its folded stacks are written to `path`.
+pub fn run_profile<A: Display, B: Display>(
+    part_one: impl Fn(&str) -> Option<A>,
+    part_two: impl Fn(&str) -> Option<B>,
+    day: Day,
+    options: &RunOptions,
+) -> io::Result<()> {
+    let [one, two] = options.read_inputs(day)?;
+    let samples = match &options.mode {
+        Mode::Profile { samples } => samples.as_deref(),
+        _ => None,
+    };
+    let exit = |e: String| -> ! {
+        eprintln!("Could not sample day {day}: {e}");
+        process::exit(1);
+    };
+
+    let sampler = samples.map(|_| Sampler::start().unwrap_or_else(|e| exit(e)));
+    if options.runs(1) {
+        profile_part(part_one, one.as_str(), day, 1);
+    }
+    if options.runs(2) {
+        profile_part(part_two, two.as_str(), day, 2);
+    }
+
+    if let (Some(sampler), Some(path)) = (sampler, samples) {
+        let (folded, dropped) = sampler.stop().unwrap_or_else(|e| exit(e));
+        if dropped > 0 {
+            eprintln!("Warning: {dropped} samples did not fit into the buffer and were dropped.");
+        }
+        fs::write(path, flamegraph::format_folded(&folded))?;
+    }
+    Ok(())
+}
+
+fn profile_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+    let timer = Instant::now();
+    let mut rounds = 0;
+    let mut report;
+
+    loop {
+        report = solve_part(&func, input.clone(), day, part, true);
+        rounds += 1;
+        if timer.elapsed() >= PROFILE_DURATION {
+            break;
+        }
+    }
+
+    println!(
+        "{} {ANSI_ITALIC}({rounds} bench runs){ANSI_RESET}",
+        report.to_human()
+    );
+}
+
+/// Run a solution part. The function is executed once, unless `time` is set:
+/// then it is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+/// These limits can be changed in the `[bench]` section of `aoc.toml`, which can also pin benched parts to a CPU core.
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
+    time: bool,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Stats) {
     let timer = Instant::now();
     let result = func(input.clone());
     let base_time = timer.elapsed();
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    let stats = if time {
         bench(func, input, &base_time)
     } else {
-        (base_time, 1)
+        Stats::from_samples(&[base_time])
     };
 
-    (result, run.0, run.1)
+    (result, stats)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
+fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> Stats {
+    let settings = &config::get().bench;
+    if let Some(cpu) = settings.pin_cpu {
+        bench_env::pin_once(cpu);
+    }
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    let bench_iterations = (settings.target.as_nanos() / cmp::max(base_time.as_nanos(), 10))
+        .clamp(settings.min_samples, settings.max_samples);
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    measure(func, input, bench_iterations)
+}
 
+/// Returns the execution times of `func` over `iterations` runs.
+fn measure<I: Clone, T>(func: impl Fn(I) -> T, input: I, iterations: u128) -> Stats {
     let mut timers: Vec<Duration> = vec![];
 
-    for _ in 0..bench_iterations {
+    for _ in 0..iterations {
         // need a clone here to make the borrow checker happy.
         let cloned = input.clone();
         let timer = Instant::now();
@@ -69,99 +233,355 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         timers.push(timer.elapsed());
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    Stats::from_samples(&timers)
 }
 
-fn average_duration(numbers: &[Duration]) -> u128 {
-    numbers
-        .iter()
-        .map(std::time::Duration::as_nanos)
-        .sum::<u128>()
-        / numbers.len() as u128
+/// Reads arguments of a solution's own, e.g. `--bag` on day 2. Runs once before any part so that parsing and file
+/// reads are not timed and invalid arguments are reported right away.
+pub type Configure = fn(&mut ExtraArgs) -> Result<(), String>;
+
+/// Entry point used by the `solution!` macro before anything runs. Passes the arguments the template does not know to
+/// the solution's [`Configure`] hook, if it has one, and fails with the arguments it did not read.
+pub fn run_configure(configure: Option<Configure>, options: &RunOptions) -> Result<(), String> {
+    let mut args = options.extra_args();
+    if let Some(configure) = configure {
+        configure(&mut args)?;
+    }
+    args.finish()
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
-    } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
-    }
-}
-
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
-    let is_intermediate_result = duration_str.is_empty();
-
-    match result {
-        Some(result) => {
-            if result.to_string().contains('\n') {
-                let str = format!("{part}: ▼ {duration_str}");
-                if is_intermediate_result {
-                    print!("{str}");
-                } else {
-                    print!("\r");
-                    println!("{str}");
-                    println!("{result}");
-                }
-            } else {
-                let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
-                if is_intermediate_result {
-                    print!("{str}");
-                } else {
-                    print!("\r");
-                    println!("{str}");
-                }
-            }
+/// Explains how a solution arrives at its result for a value of the puzzle input, e.g. a seed on day 5.
+/// Receives the puzzle input and the value passed via `--explain <value>`.
+pub type Explain = fn(&str, &str);
+
+/// Entry point used by the `solution!` macro when a solution is invoked with `--explain <value>`.
+pub fn run_explain(explain: Explain, day: Day, value: &str, input: &Input) -> io::Result<()> {
+    explain(&input.read(day)?, value);
+    Ok(())
+}
+
+/// Exits when a mode is requested that the solution does not register, e.g. `--scale` without a generator.
+pub fn unsupported(day: Day, what: &str) -> ! {
+    eprintln!("Day {day} does not register {what}, see `solution!`.");
+    process::exit(1);
+}
+
+/// An implementation of a part, see [`run_variants`]. Answers are compared as strings, so variants may return
+/// different types.
+#[derive(Clone, Copy)]
+pub struct Variant {
+    pub part: u8,
+    pub name: &'static str,
+    pub func: fn(&str) -> Option<String>,
+}
+
+/// Entry point used by the `solution!` macro when a solution is invoked with `--variants`.
+/// Benches `part_one` and `part_two` together with the variants registered via
+/// `solution!(<day>, variants = [<part> => <fn>])`, prints their speedups relative to the registered part and
+/// exits with an error if they disagree on an answer.
+pub fn run_variants(
+    parts: [Variant; 2],
+    variants: &[Variant],
+    day: Day,
+    options: &RunOptions,
+) -> io::Result<()> {
+    if variants.is_empty() {
+        unsupported(day, "variants");
+    }
+    if let Some(variant) = variants.iter().find(|v| !matches!(v.part, 1 | 2)) {
+        eprintln!(
+            "Variant {} is registered for part {}, expected 1 or 2.",
+            variant.name, variant.part
+        );
+        process::exit(1);
+    }
+    let inputs = options.read_inputs(day)?;
+    let mut agree = true;
+
+    for (i, base) in parts.iter().filter(|p| options.runs(p.part)).enumerate() {
+        if i > 0 {
+            println!();
         }
-        None => {
-            if is_intermediate_result {
-                print!("{part}: ✖");
-            } else {
-                print!("\r");
-                println!("{part}: ✖             ");
-            }
+        println!("{ANSI_BOLD}Part {}{ANSI_RESET}", base.part);
+
+        let rows: Vec<(&str, RunReport)> = std::iter::once(base)
+            .chain(variants.iter().filter(|v| v.part == base.part))
+            .map(|v| {
+                (
+                    v.name,
+                    solve_part(
+                        v.func,
+                        inputs[usize::from(v.part - 1)].as_str(),
+                        day,
+                        v.part,
+                        true,
+                    ),
+                )
+            })
+            .collect();
+
+        print!("{}", format_variants_table(&rows));
+
+        if !answers_agree(&rows) {
+            agree = false;
+            println!("{ANSI_BOLD}✖ variants disagree on the answer.{ANSI_RESET}");
         }
     }
+
+    if !agree {
+        process::exit(1);
+    }
+    Ok(())
 }
 
-/// Parse the arguments passed to `solve` and try to submit one part of the solution if:
-///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
-    day: Day,
-    part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
-    let args: Vec<String> = env::args().collect();
+fn answers_agree(rows: &[(&str, RunReport)]) -> bool {
+    rows.windows(2).all(|w| w[0].1.answer == w[1].1.answer)
+}
 
-    if !args.contains(&"--submit".into()) {
-        return None;
+/// Compares the mean times of the variants to the first one, e.g. `4.00x` for a variant that takes a quarter of the time.
+fn format_variants_table(rows: &[(&str, RunReport)]) -> String {
+    let mean = |report: &RunReport| report.stats.map_or(Duration::ZERO, |s| s.mean);
+    let base = rows
+        .first()
+        .map_or(Duration::ZERO, |(_, report)| mean(report));
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    let mut lines = vec![format!(
+        "{:<width$} | {:>12} | {:>12} | {:>8}",
+        "variant", "answer", "time", "speedup"
+    )];
+
+    for (name, report) in rows {
+        let answer = match &report.answer {
+            Some(answer) if answer.contains('\n') => "▼".to_string(),
+            Some(answer) => answer.clone(),
+            None => "✖".to_string(),
+        };
+        let speedup = base.as_secs_f64() / mean(report).as_secs_f64().max(f64::MIN_POSITIVE);
+        lines.push(format!(
+            "{name:<width$} | {answer:>12} | {:>12} | {:>7.2}x",
+            format!("{:.1?}", mean(report)),
+            speedup
+        ));
     }
 
-    if args.len() < 3 {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
+    lines.join("\n") + "\n"
+}
+
+/// Seed used for generated inputs so that scaling runs are comparable.
+const SCALE_SEED: u64 = 2023;
+const SCALE_MIN_SIZE: usize = 8;
+const SCALE_MAX_SIZE: usize = 256;
+const SCALE_BAR_WIDTH: usize = 40;
+
+/// Runs both parts on generated inputs of doubling size and estimates their empirical complexity.
+/// Sizes start at 8 and grow until `max_size` (default: 256) or until a single run takes longer than a second.
+pub fn run_scaling<A, B>(
+    part_one: impl Fn(&str) -> A,
+    part_two: impl Fn(&str) -> B,
+    generator: Generator,
+    max_size: Option<usize>,
+) {
+    let max_size = max_size.unwrap_or(SCALE_MAX_SIZE);
+
+    scale_part("Part 1", part_one, generator, max_size);
+    println!();
+    scale_part("Part 2", part_two, generator, max_size);
+}
+
+fn scale_part<T>(name: &str, func: impl Fn(&str) -> T, generator: Generator, max_size: usize) {
+    println!("{ANSI_BOLD}{name}{ANSI_RESET}");
+
+    let mut rng = StdRng::seed_from_u64(SCALE_SEED);
+    let mut samples: Vec<(usize, Duration)> = vec![];
+    let mut size = SCALE_MIN_SIZE;
+
+    while size <= max_size {
+        let input = generator(&mut rng, size);
+
+        let timer = Instant::now();
+        func(&input);
+        let base_time = timer.elapsed();
+
+        let iterations = (Duration::from_millis(200).as_nanos()
+            / cmp::max(base_time.as_nanos(), 10))
+        .clamp(3, 10000);
+
+        samples.push((size, measure(&func, input.as_str(), iterations).mean));
+
+        if base_time > Duration::from_secs(1) {
+            break;
+        }
+        size *= 2;
     }
 
-    let part_index = args.iter().position(|x| x == "--submit").unwrap() + 1;
+    print!("{}", format_scaling_table(&samples));
 
-    let Ok(part_submit) = args[part_index].parse::<u8>() else {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
-    };
+    match fit_exponent(&samples) {
+        Some(exponent) => println!(
+            "empirical complexity: {ANSI_BOLD}O(n^{exponent:.2}){ANSI_RESET} {ANSI_ITALIC}(n is the generator size){ANSI_RESET}"
+        ),
+        None => println!("not enough samples to estimate complexity."),
+    }
+}
+
+fn format_scaling_table(samples: &[(usize, Duration)]) -> String {
+    let logs: Vec<f64> = samples
+        .iter()
+        .map(|(_, d)| (d.as_nanos().max(1) as f64).ln())
+        .collect();
+    let min = logs.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    let mut lines = vec![format!("{:>8} | {:>12} |", "size", "time")];
 
-    if part_submit != part {
+    for ((size, duration), log) in samples.iter().zip(logs) {
+        let ratio = if max > min {
+            (log - min) / (max - min)
+        } else {
+            0.0
+        };
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let bar = 1 + (ratio * (SCALE_BAR_WIDTH - 1) as f64).round() as usize;
+        lines.push(format!(
+            "{size:>8} | {:>12} | {}",
+            format!("{duration:.1?}"),
+            "█".repeat(bar)
+        ));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Fits `time = c * size^k` to the samples with a least squares regression on a log-log scale and returns `k`.
+fn fit_exponent(samples: &[(usize, Duration)]) -> Option<f64> {
+    if samples.len() < 2 {
         return None;
     }
 
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|(size, d)| ((*size as f64).ln(), (d.as_nanos().max(1) as f64).ln()))
+        .collect();
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+
+    let covariance: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+/// Prints the answer of a part as soon as it is known, before benching. The line is completed by [`RunReport::to_human`].
+fn print_intermediate(answer: Option<&str>, part: u8) {
+    match answer {
+        Some(answer) if answer.contains('\n') => print!("Part {part}: ▼ "),
+        Some(answer) => print!("Part {part}: {ANSI_BOLD}{answer}{ANSI_RESET}"),
+        None => print!("Part {part}: ✖"),
+    }
+}
+
+/// Submits the answer of a part via aoc-cli, if it is installed.
+fn submit_result(answer: &str, day: Day, part: u8) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+        return;
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    if let Err(e) = aoc_cli::submit(day, part, answer) {
+        eprintln!("failed to call aoc-cli: {e}");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        answers_agree, fit_exponent, format_scaling_table, format_variants_table, run_configure,
+    };
+    use crate::day;
+    use crate::template::options::{ExtraArgs, RunOptions};
+    use crate::template::report::{RunReport, Stats, Status};
+    use std::time::Duration;
+
+    fn report(answer: &str, micros: u64) -> RunReport {
+        let mean = Duration::from_micros(micros);
+        RunReport {
+            day: day!(7),
+            part: 2,
+            status: Status::Solved,
+            answer: Some(answer.into()),
+            stats: Some(Stats {
+                samples: 10,
+                mean,
+                min: mean,
+                max: mean,
+            }),
+        }
+    }
+
+    #[test]
+    fn compares_variants() {
+        let rows = [
+            ("part_two", report("5905", 10)),
+            ("part_two_replacing", report("5905", 40)),
+        ];
+        let table = format_variants_table(&rows);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("part_two           |         5905 |       10.0µs |    1.00x"));
+        assert!(lines[2].ends_with("|    0.25x"));
+        assert!(answers_agree(&rows));
+        assert!(!answers_agree(&[
+            rows[0].clone(),
+            ("naive", report("5904", 1))
+        ]));
+    }
+
+    #[test]
+    fn fits_quadratic_exponent() {
+        let samples: Vec<_> = [8, 16, 32, 64]
+            .iter()
+            .map(|&n| (n, Duration::from_nanos((n * n * 10) as u64)))
+            .collect();
+        let exponent = fit_exponent(&samples).unwrap();
+        assert!((exponent - 2.0).abs() < 0.01, "{exponent}");
+    }
+
+    #[test]
+    fn requires_two_samples() {
+        assert_eq!(fit_exponent(&[(8, Duration::from_micros(1))]), None);
+    }
+
+    #[test]
+    fn formats_scaling_table() {
+        let table = format_scaling_table(&[
+            (8, Duration::from_micros(1)),
+            (16, Duration::from_micros(4)),
+        ]);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("       8 |        1.0µs | █"));
+        assert!(lines[2].ends_with(&"█".repeat(40)));
+    }
+
+    #[test]
+    fn rejects_arguments_the_solution_does_not_read() {
+        fn configure(args: &mut ExtraArgs) -> Result<(), String> {
+            args.value::<u32>("--bag").map(|_| ())
+        }
+
+        let options = RunOptions::parse(vec!["--bag".into(), "1".into()]).unwrap();
+        assert_eq!(run_configure(Some(configure), &options), Ok(()));
+        assert_eq!(
+            run_configure(None, &options).unwrap_err(),
+            "unknown argument(s): --bag 1"
+        );
+        assert_eq!(run_configure(None, &RunOptions::default()), Ok(()));
+    }
 }
@@ -0,0 +1,393 @@
+/// A built-in sampling profiler for `cargo profile`, used when `perf` is not installed.
+/// A `SIGPROF` timer interrupts the process at a fixed rate of CPU time. The handler follows the frame pointers of the
+/// interrupted code and records the return addresses into a buffer that is allocated up front, as a signal handler
+/// must not lock or allocate. Once sampling stops, the addresses are resolved with the symbol table of the running
+/// binary and collapsed into [`Folded`] stacks.
+/// Callers are only found through functions built with frame pointers, which `cargo profile` enables. Sampling is
+/// supported on x86_64 and aarch64 Linux with glibc.
+use std::{collections::HashMap, fs};
+
+use object::{Object, ObjectSegment, ObjectSymbol, SymbolKind};
+
+use super::flamegraph::{self, Folded};
+
+/// Samples per second of CPU time, the same odd rate `perf` is run with. The resolution of the kernel's timers may
+/// lower the actual rate, e.g. to 250 samples per second.
+const FREQUENCY: u64 = 997;
+/// Enough for both parts at [`FREQUENCY`] during the runner's profile loop, later samples are dropped.
+const MAX_SAMPLES: usize = 16_384;
+/// Frames per sample, deeper stacks are cut off at the root.
+const MAX_DEPTH: usize = 128;
+/// Frames in shared libraries like libc are not in the symbol table of the binary.
+const UNKNOWN_FRAME: &str = "[unknown]";
+
+/// Collects samples until [`Sampler::stop`] is called.
+pub struct Sampler(());
+
+impl Sampler {
+    /// Starts sampling the current process. Stacks are only walked on the calling thread.
+    pub fn start() -> Result<Self, String> {
+        sys::start()?;
+        Ok(Self(()))
+    }
+
+    /// Stops sampling and returns the sampled stacks, plus the number of samples that did not fit into the buffer.
+    pub fn stop(self) -> Result<(Folded, usize), String> {
+        let (stacks, dropped) = sys::stop();
+        let symbols = Symbols::load()?;
+
+        let mut names: HashMap<usize, &str> = HashMap::new();
+        let stacks = stacks.into_iter().map(|stack| {
+            stack
+                .into_iter()
+                .map(|ip| {
+                    *names
+                        .entry(ip)
+                        .or_insert_with(|| symbols.name(ip).unwrap_or(UNKNOWN_FRAME))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        Ok((flamegraph::collapse(stacks), dropped))
+    }
+}
+
+/// Function symbols of the running binary, to turn sampled addresses into names.
+struct Symbols {
+    /// Difference between the addresses functions are loaded at and the addresses in the symbol table.
+    bias: usize,
+    /// Start, size and demangled name, sorted by start.
+    functions: Vec<(u64, u64, String)>,
+}
+
+impl Symbols {
+    fn load() -> Result<Self, String> {
+        let exe = fs::read_link("/proc/self/exe").map_err(|e| e.to_string())?;
+        let data = fs::read(&exe).map_err(|e| format!("could not read {}: {e}", exe.display()))?;
+        let file = object::File::parse(data.as_slice())
+            .map_err(|e| format!("could not parse {}: {e}", exe.display()))?;
+
+        let base = fs::read_to_string("/proc/self/maps")
+            .map_err(|e| e.to_string())?
+            .lines()
+            .find_map(|line| parse_mapping(line, &exe.to_string_lossy()))
+            .ok_or_else(|| format!("{} is not mapped into memory", exe.display()))?;
+        // the segment that starts at the beginning of the file is the one mapped at `base`.
+        let vaddr = file
+            .segments()
+            .find(|segment| segment.file_range().0 == 0)
+            .map(|segment| segment.address())
+            .ok_or_else(|| format!("{} has no loadable segments", exe.display()))?;
+
+        let mut functions: Vec<(u64, u64, String)> = file
+            .symbols()
+            .filter(|symbol| symbol.kind() == SymbolKind::Text && symbol.address() != 0)
+            .filter_map(|symbol| {
+                let name = rustc_demangle::demangle(symbol.name().ok()?);
+                Some((symbol.address(), symbol.size(), format!("{name:#}")))
+            })
+            .collect();
+        if functions.is_empty() {
+            return Err(format!(
+                "{} has no symbols, it may have been stripped",
+                exe.display()
+            ));
+        }
+        functions.sort_unstable_by_key(|(start, _, _)| *start);
+
+        #[allow(clippy::cast_possible_truncation)]
+        let bias = base.wrapping_sub(vaddr as usize);
+        Ok(Self { bias, functions })
+    }
+
+    fn name(&self, ip: usize) -> Option<&str> {
+        // return addresses point behind the call, which may already belong to the next function.
+        let address = ip.wrapping_sub(self.bias).wrapping_sub(1) as u64;
+        let index = self
+            .functions
+            .partition_point(|(start, _, _)| *start <= address);
+        let (start, size, name) = self.functions.get(index.checked_sub(1)?)?;
+        (address < start + size).then_some(name.as_str())
+    }
+}
+
+/// Reads the start address of a line of `/proc/self/maps` if it maps the beginning of `path`,
+/// e.g. `55d4c0a00000-55d4c0a2f000 r--p 00000000 08:01 1234 /target/release/01`.
+fn parse_mapping(line: &str, path: &str) -> Option<usize> {
+    let mut fields = line.split_whitespace();
+    let range = fields.next()?;
+    let offset = fields.nth(1)?;
+    let mapped = fields.nth(2)?;
+
+    if mapped != path || u64::from_str_radix(offset, 16).ok()? != 0 {
+        return None;
+    }
+    usize::from_str_radix(range.split_once('-')?.0, 16).ok()
+}
+
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod sys {
+    use std::{
+        ffi::{c_int, c_void},
+        mem, ptr,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            OnceLock,
+        },
+    };
+
+    use libc::{siginfo_t, ucontext_t};
+
+    use super::{FREQUENCY, MAX_DEPTH, MAX_SAMPLES};
+
+    /// One slot per sample: the number of frames followed by their addresses, leaf first.
+    const SLOT: usize = MAX_DEPTH + 1;
+    /// Atomics, because the signal handler may run on any thread and must not allocate or lock.
+    static BUFFER: OnceLock<Box<[AtomicUsize]>> = OnceLock::new();
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    static RUNNING: AtomicBool = AtomicBool::new(false);
+    /// Bounds of the stack of the thread that started sampling. Frame pointers are only followed within it, so a
+    /// register that holds something else is never dereferenced.
+    static STACK_LOW: AtomicUsize = AtomicUsize::new(0);
+    static STACK_HIGH: AtomicUsize = AtomicUsize::new(0);
+
+    /// Program counter, stack pointer and frame pointer of the interrupted code.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn registers(context: &ucontext_t) -> (usize, usize, usize) {
+        #[cfg(target_arch = "x86_64")]
+        {
+            let registers = &context.uc_mcontext.gregs;
+            (
+                registers[libc::REG_RIP as usize] as usize,
+                registers[libc::REG_RSP as usize] as usize,
+                registers[libc::REG_RBP as usize] as usize,
+            )
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            let registers = &context.uc_mcontext;
+            (
+                registers.pc as usize,
+                registers.sp as usize,
+                registers.regs[29] as usize,
+            )
+        }
+    }
+
+    extern "C" fn on_sample(_signal: c_int, _info: *mut siginfo_t, context: *mut c_void) {
+        let Some(buffer) = BUFFER.get() else {
+            return;
+        };
+        let sample = NEXT.fetch_add(1, Ordering::Relaxed);
+        let Some(slot) = buffer.get(sample * SLOT..(sample + 1) * SLOT) else {
+            return;
+        };
+
+        // SAFETY: handlers installed with `SA_SIGINFO` are passed the context of the interrupted code.
+        let (pc, sp, mut fp) = registers(unsafe { &*context.cast::<ucontext_t>() });
+        // the other frames are return addresses, which point behind a call, see `Symbols::name`.
+        slot[1].store(pc.wrapping_add(1), Ordering::Relaxed);
+        let mut depth = 1;
+
+        let high = STACK_HIGH.load(Ordering::Relaxed);
+        let on_sampled_thread = (STACK_LOW.load(Ordering::Relaxed)..high).contains(&sp);
+        // a frame starts with the frame pointer of its caller, followed by the return address. Callers are further
+        // up the stack, which is mapped between the stack pointer and its end.
+        let mut low = sp;
+        while on_sampled_thread
+            && depth < MAX_DEPTH
+            && fp >= low
+            && fp % mem::align_of::<usize>() == 0
+            && fp + 2 * mem::size_of::<usize>() <= high
+        {
+            let frame = fp as *const usize;
+            // SAFETY: `frame` is aligned and both words are within the mapped part of the sampled thread's stack.
+            let (caller, ip) = unsafe { (ptr::read(frame), ptr::read(frame.add(1))) };
+            if ip == 0 {
+                break;
+            }
+            slot[depth + 1].store(ip, Ordering::Relaxed);
+            depth += 1;
+            low = fp + 2 * mem::size_of::<usize>();
+            fp = caller;
+        }
+        slot[0].store(depth, Ordering::Relaxed);
+    }
+
+    /// The lowest and highest address of the stack of the current thread.
+    fn stack_bounds() -> Option<(usize, usize)> {
+        // SAFETY: `attributes` is initialized by `pthread_getattr_np` before it is read and destroyed afterwards.
+        unsafe {
+            let mut attributes: libc::pthread_attr_t = mem::zeroed();
+            if libc::pthread_getattr_np(libc::pthread_self(), &raw mut attributes) != 0 {
+                return None;
+            }
+            let mut low = ptr::null_mut();
+            let mut size = 0;
+            let result =
+                libc::pthread_attr_getstack(&raw const attributes, &raw mut low, &raw mut size);
+            libc::pthread_attr_destroy(&raw mut attributes);
+            (result == 0).then(|| (low as usize, low as usize + size))
+        }
+    }
+
+    fn set_timer(micros: libc::suseconds_t) -> Result<(), String> {
+        let interval = libc::timeval {
+            tv_sec: 0,
+            tv_usec: micros,
+        };
+        let timer = libc::itimerval {
+            it_interval: interval,
+            it_value: interval,
+        };
+        // SAFETY: `timer` is a valid `itimerval`, the old value is not requested.
+        if unsafe { libc::setitimer(libc::ITIMER_PROF, &raw const timer, ptr::null_mut()) } != 0 {
+            return Err("could not start the sampling timer".into());
+        }
+        Ok(())
+    }
+
+    pub fn start() -> Result<(), String> {
+        if RUNNING.swap(true, Ordering::SeqCst) {
+            return Err("the sampler is already running".into());
+        }
+        let stop = |e: &str| {
+            RUNNING.store(false, Ordering::SeqCst);
+            Err(e.to_string())
+        };
+
+        BUFFER.get_or_init(|| {
+            (0..MAX_SAMPLES * SLOT)
+                .map(|_| AtomicUsize::new(0))
+                .collect()
+        });
+        NEXT.store(0, Ordering::SeqCst);
+        let Some((low, high)) = stack_bounds() else {
+            return stop("could not find the bounds of the stack");
+        };
+        STACK_LOW.store(low, Ordering::SeqCst);
+        STACK_HIGH.store(high, Ordering::SeqCst);
+
+        // SAFETY: an all-zero `sigaction` is valid, the fields that matter are set below.
+        let mut action: libc::sigaction = unsafe { mem::zeroed() };
+        action.sa_sigaction =
+            on_sample as extern "C" fn(c_int, *mut siginfo_t, *mut c_void) as libc::sighandler_t;
+        // restart interrupted system calls, so the profiled code does not see `EINTR`.
+        action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART;
+        // SAFETY: `on_sample` is async-signal-safe: it reads the interrupted thread's stack within its bounds and
+        // only writes to atomics of the pre-allocated buffer.
+        if unsafe { libc::sigaction(libc::SIGPROF, &raw const action, ptr::null_mut()) } != 0 {
+            return stop("could not install the sampling signal handler");
+        }
+        #[allow(clippy::cast_possible_wrap)]
+        set_timer((1_000_000 / FREQUENCY) as libc::suseconds_t)
+    }
+
+    pub fn stop() -> (Vec<Vec<usize>>, usize) {
+        let _ = set_timer(0);
+        // a signal that is still pending would terminate the process with the default action.
+        // SAFETY: ignoring a signal has no preconditions.
+        unsafe { libc::signal(libc::SIGPROF, libc::SIG_IGN) };
+        RUNNING.store(false, Ordering::SeqCst);
+
+        let Some(buffer) = BUFFER.get() else {
+            return (vec![], 0);
+        };
+        let taken = NEXT.load(Ordering::SeqCst);
+        let stacks = buffer
+            .chunks(SLOT)
+            .take(taken)
+            .map(|slot| {
+                let depth = slot[0].load(Ordering::Relaxed);
+                slot[1..=depth]
+                    .iter()
+                    .map(|ip| ip.load(Ordering::Relaxed))
+                    .collect()
+            })
+            .collect();
+        (stacks, taken.saturating_sub(MAX_SAMPLES))
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    target_env = "gnu",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod sys {
+    pub fn start() -> Result<(), String> {
+        Err(
+            "the built-in sampler only supports x86_64 and aarch64 Linux, install \"perf\" instead"
+                .into(),
+        )
+    }
+
+    pub fn stop() -> (Vec<Vec<usize>>, usize) {
+        (vec![], 0)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::parse_mapping;
+
+    #[test]
+    fn parses_mappings() {
+        let path = "/target/release/01";
+        assert_eq!(
+            parse_mapping(
+                "55d4c0a00000-55d4c0a2f000 r--p 00000000 08:01 1234  /target/release/01",
+                path
+            ),
+            Some(0x55d4_c0a0_0000)
+        );
+        assert_eq!(
+            parse_mapping(
+                "55d4c0a2f000-55d4c0b00000 r-xp 0002f000 08:01 1234  /target/release/01",
+                path
+            ),
+            None
+        );
+        assert_eq!(
+            parse_mapping("7f0000000000-7f0000001000 rw-p 00000000 00:00 0", path),
+            None
+        );
+    }
+
+    #[cfg(all(
+        target_os = "linux",
+        target_env = "gnu",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    #[test]
+    fn samples_own_stack() {
+        use super::{Sampler, Symbols};
+        use std::{hint::black_box, time::Instant};
+
+        #[inline(never)]
+        fn spin() -> u64 {
+            let start = Instant::now();
+            let mut x = 0u64;
+            while start.elapsed().as_millis() < 300 {
+                x = black_box(x.wrapping_mul(31).wrapping_add(7));
+            }
+            x
+        }
+
+        let symbols = Symbols::load().unwrap();
+        assert_eq!(
+            symbols.name(spin as fn() -> u64 as usize + 1),
+            Some("advent_of_code::template::sampler::tests::samples_own_stack::spin")
+        );
+
+        let sampler = Sampler::start().unwrap();
+        spin();
+        let (folded, dropped) = sampler.stop().unwrap();
+        assert_eq!(dropped, 0);
+        assert!(folded.keys().any(|stack| stack.contains("::spin")));
+    }
+}
@@ -0,0 +1,131 @@
+/// Keeps puzzle inputs and descriptions (which contain our answers) out of the repository, as Advent of Code asks
+/// for them not to be published.
+/// With `paths.store` configured, they live in that git-ignored directory instead of `data/`. A manifest of their
+/// hashes is committed, so a missing or outdated store is noticed when reading an input.
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use crate::template::{config, hash::fnv1a};
+
+/// Data folders that must never be committed.
+pub const SECRET_FOLDERS: [&str; 2] = ["inputs", "puzzles"];
+
+/// The directory inputs and puzzles are stored in, if the git-ignored store is enabled.
+#[must_use]
+pub fn store() -> Option<PathBuf> {
+    config::get().paths.store.clone()
+}
+
+/// The manifest is committed, so it lives in the data folder rather than the store.
+#[must_use]
+pub fn manifest_path() -> PathBuf {
+    config::get().paths.data.join("secrets.manifest")
+}
+
+fn is_secret(folder: &str) -> bool {
+    SECRET_FOLDERS.contains(&folder)
+}
+
+/// Where `file` of a data `folder` lives, taking the store into account.
+#[must_use]
+pub fn path(folder: &str, file: &str) -> PathBuf {
+    match store() {
+        Some(store) if is_secret(folder) => store.join(folder).join(file),
+        _ => config::get().data_dir(folder).join(file),
+    }
+}
+
+/// Hashes of secret files, keyed by `<folder>/<file>`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Manifest(BTreeMap<String, u64>);
+
+impl Manifest {
+    /// Reads the manifest, which is empty if it does not exist yet.
+    #[must_use]
+    pub fn load() -> Self {
+        fs::read_to_string(manifest_path())
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    fn parse(content: &str) -> Self {
+        Self(
+            content
+                .lines()
+                .filter_map(|line| {
+                    let (key, hash) = line.split_once(' ')?;
+                    Some((key.to_string(), u64::from_str_radix(hash, 16).ok()?))
+                })
+                .collect(),
+        )
+    }
+
+    fn format(&self) -> String {
+        self.0
+            .iter()
+            .map(|(key, hash)| format!("{key} {hash:016x}\n"))
+            .collect()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        fs::write(manifest_path(), self.format())
+    }
+
+    pub fn insert(&mut self, folder: &str, file: &str, content: &[u8]) {
+        self.0.insert(format!("{folder}/{file}"), fnv1a(content));
+    }
+
+    #[must_use]
+    pub fn get(&self, folder: &str, file: &str) -> Option<u64> {
+        self.0.get(&format!("{folder}/{file}")).copied()
+    }
+
+    pub fn hashes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.0.values().copied()
+    }
+}
+
+/// Records the hash of a file that was just written to the store. Does nothing without a store.
+pub fn record(folder: &str, file: &str) -> std::io::Result<()> {
+    if store().is_none() || !is_secret(folder) {
+        return Ok(());
+    }
+    let content = fs::read(path(folder, file))?;
+    let mut manifest = Manifest::load();
+    manifest.insert(folder, file, &content);
+    manifest.save()
+}
+
+/// Checks a file read from the store against the manifest.
+/// Returns an error message if the store holds a different file than the one the manifest was written for.
+pub fn verify(folder: &str, file: &str, content: &str) -> Result<(), String> {
+    if store().is_none() || !is_secret(folder) {
+        return Ok(());
+    }
+    match Manifest::load().get(folder, file) {
+        Some(expected) if expected != fnv1a(content.as_bytes()) => Err(format!(
+            "{} does not match its hash in {}. Try running \"cargo download\" again.",
+            path(folder, file).display(),
+            manifest_path().display()
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{fnv1a, Manifest};
+
+    #[test]
+    fn round_trips_manifest() {
+        let mut manifest = Manifest::default();
+        manifest.insert("inputs", "01.txt", b"1abc2");
+        manifest.insert("puzzles", "01.md", b"");
+        let formatted = manifest.format();
+        assert_eq!(
+            formatted.lines().next(),
+            Some(format!("inputs/01.txt {:016x}", fnv1a(b"1abc2")).as_str())
+        );
+        assert_eq!(Manifest::parse(&formatted), manifest);
+        assert_eq!(manifest.get("inputs", "02.txt"), None);
+    }
+}
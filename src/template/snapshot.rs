@@ -0,0 +1,156 @@
+/// Snapshot tests for multi-line answers and debug renderings, e.g. of [`crate::template::viz`].
+/// Expected values are stored in `data/snapshots/<name>.snap`. If a value differs from its snapshot or there is no
+/// snapshot yet, it is written to `<name>.snap.new` and the test fails. `cargo review` accepts or rejects these.
+use std::{fs, io, path::PathBuf};
+
+use crate::template::{config, ocr};
+
+const EXTENSION: &str = "snap";
+const PENDING_EXTENSION: &str = "snap.new";
+
+#[must_use]
+pub fn folder() -> PathBuf {
+    config::get().data_dir("snapshots")
+}
+
+/// Where the accepted snapshot `name` is stored.
+#[must_use]
+pub fn path(name: &str) -> PathBuf {
+    folder().join(format!("{name}.{EXTENSION}"))
+}
+
+/// Where a changed value of snapshot `name` waits for review.
+#[must_use]
+pub fn pending_path(name: &str) -> PathBuf {
+    folder().join(format!("{name}.{PENDING_EXTENSION}"))
+}
+
+/// Snapshots end with exactly one newline, which keeps them friendly to editors.
+fn normalize(value: &str) -> String {
+    format!("{}\n", value.trim_end_matches('\n'))
+}
+
+/// Compares `actual` to the snapshot `name`. If they differ, `actual` is saved for review and the test fails with a
+/// diff. Use [`crate::assert_snapshot!`] to pass anything that implements `Display`.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let actual = normalize(actual);
+    let expected = fs::read_to_string(path(name)).ok();
+
+    if expected.as_deref() == Some(actual.as_str()) {
+        // the value may have been fixed since the last run.
+        let _ = fs::remove_file(pending_path(name));
+        return;
+    }
+
+    if let Err(e) =
+        fs::create_dir_all(folder()).and_then(|()| fs::write(pending_path(name), &actual))
+    {
+        panic!("could not save snapshot `{name}` for review: {e}");
+    }
+
+    let details = match expected {
+        Some(expected) => format!("does not match:\n{}", diff(&expected, &actual)),
+        None => format!(
+            "does not exist yet, the value is:\n{actual}{}",
+            reads_as(&actual)
+        ),
+    };
+    panic!("snapshot `{name}` {details}\nReview it with `cargo review`.");
+}
+
+/// Notes which letters a value spells if it is block-letter art, see [`ocr`].
+fn reads_as(value: &str) -> String {
+    ocr::read(value).map_or(String::new(), |letters| format!(" (reads as `{letters}`)"))
+}
+
+/// A line by line comparison of two values, which are usually of the same shape.
+#[must_use]
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected_lines: Vec<_> = expected.lines().collect();
+    let actual_lines: Vec<_> = actual.lines().collect();
+
+    let mut lines = vec![
+        format!("--- snapshot{}", reads_as(expected)),
+        format!("+++ value{}", reads_as(actual)),
+    ];
+
+    for i in 0..expected_lines.len().max(actual_lines.len()) {
+        match (expected_lines.get(i), actual_lines.get(i)) {
+            (Some(e), Some(a)) if e == a => lines.push(format!("  {e}")),
+            (e, a) => {
+                lines.extend(e.map(|e| format!("- {e}")));
+                lines.extend(a.map(|a| format!("+ {a}")));
+            }
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Names of the snapshots waiting for review, sorted.
+pub fn pending() -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(folder()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    let mut names = vec![];
+    for entry in entries {
+        let file_name = entry?.file_name();
+        if let Some(name) = file_name
+            .to_string_lossy()
+            .strip_suffix(&format!(".{PENDING_EXTENSION}"))
+        {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Replaces the snapshot `name` with its pending value.
+pub fn accept(name: &str) -> io::Result<()> {
+    fs::rename(pending_path(name), path(name))
+}
+
+/// Discards the pending value of snapshot `name`.
+pub fn reject(name: &str) -> io::Result<()> {
+    fs::remove_file(pending_path(name))
+}
+
+/// Compares a value to the snapshot stored in `data/snapshots/<name>.snap`, see [`crate::template::snapshot`].
+/// The value can be anything that implements `Display`, e.g. `assert_snapshot!("08-part-two", result.unwrap())`.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $value:expr $(,)?) => {
+        $crate::template::snapshot::assert_snapshot(&$name, &$value.to_string())
+    };
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{diff, normalize};
+
+    #[test]
+    fn normalizes_trailing_newlines() {
+        assert_eq!(normalize("a\nb"), "a\nb\n");
+        assert_eq!(normalize("a\nb\n\n"), "a\nb\n");
+    }
+
+    #[test]
+    fn diffs_lines() {
+        assert_eq!(
+            diff("a\nb\nc\n", "a\nx\n"),
+            "--- snapshot\n+++ value\n  a\n- b\n+ x\n- c\n"
+        );
+
+        let h = "#..#\n#..#\n####\n#..#\n#..#\n#..#";
+        let e = "####\n#...\n###.\n#...\n#...\n####";
+        let header: Vec<_> = diff(h, e).lines().take(2).map(String::from).collect();
+        assert_eq!(
+            header,
+            ["--- snapshot (reads as `H`)", "+++ value (reads as `E`)"]
+        );
+    }
+}
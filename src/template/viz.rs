@@ -0,0 +1,366 @@
+/// Helpers for visualizing grid-based puzzles while debugging.
+/// Frames can be printed with ANSI colours, played back as a terminal animation or exported as SVG / plain text files.
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    io::{self, stdout, Write},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use super::{ANSI_BOLD, ANSI_CLEAR, ANSI_RESET};
+
+/// Colours available for highlighting cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Colour {
+    fn ansi(self) -> &'static str {
+        match self {
+            Colour::Red => "\x1b[31m",
+            Colour::Green => "\x1b[32m",
+            Colour::Yellow => "\x1b[33m",
+            Colour::Blue => "\x1b[34m",
+            Colour::Magenta => "\x1b[35m",
+            Colour::Cyan => "\x1b[36m",
+        }
+    }
+
+    fn svg(self) -> &'static str {
+        match self {
+            Colour::Red => "#e06c75",
+            Colour::Green => "#98c379",
+            Colour::Yellow => "#e5c07b",
+            Colour::Blue => "#61afef",
+            Colour::Magenta => "#c678dd",
+            Colour::Cyan => "#56b6c2",
+        }
+    }
+
+    /// Single letter used in the highlight mask of plain text exports.
+    fn letter(self) -> char {
+        match self {
+            Colour::Red => 'R',
+            Colour::Green => 'G',
+            Colour::Yellow => 'Y',
+            Colour::Blue => 'B',
+            Colour::Magenta => 'M',
+            Colour::Cyan => 'C',
+        }
+    }
+}
+
+/// A snapshot of a character grid with highlighted cells.
+/// Coordinates are `(x, y)` pairs, so any grid type convertible into `(usize, usize)` can be used directly.
+#[derive(Debug, Clone, Default)]
+pub struct Frame {
+    cells: Vec<Vec<char>>,
+    highlights: HashMap<(usize, usize), Colour>,
+    caption: Option<String>,
+}
+
+impl Frame {
+    /// Creates a frame from a puzzle input, one row per line.
+    #[must_use]
+    pub fn new(input: &str) -> Self {
+        Self {
+            cells: input.lines().map(|l| l.chars().collect()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a frame of the given size where every cell contains `fill`.
+    #[must_use]
+    pub fn filled(width: usize, height: usize, fill: char) -> Self {
+        Self {
+            cells: vec![vec![fill; width]; height],
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.cells.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Overwrites the character of a cell. Coordinates outside of the grid are ignored.
+    pub fn set(&mut self, coord: impl Into<(usize, usize)>, value: char) -> &mut Self {
+        let (x, y) = coord.into();
+        if let Some(cell) = self.cells.get_mut(y).and_then(|row| row.get_mut(x)) {
+            *cell = value;
+        }
+        self
+    }
+
+    pub fn highlight(&mut self, coord: impl Into<(usize, usize)>, colour: Colour) -> &mut Self {
+        self.highlights.insert(coord.into(), colour);
+        self
+    }
+
+    pub fn highlight_region<C: Into<(usize, usize)>>(
+        &mut self,
+        coords: impl IntoIterator<Item = C>,
+        colour: Colour,
+    ) -> &mut Self {
+        for coord in coords {
+            self.highlight(coord, colour);
+        }
+        self
+    }
+
+    pub fn clear_highlights(&mut self) -> &mut Self {
+        self.highlights.clear();
+        self
+    }
+
+    pub fn caption(&mut self, caption: impl Into<String>) -> &mut Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Renders the frame with ANSI colour codes for printing to a terminal.
+    #[must_use]
+    pub fn render_ansi(&self) -> String {
+        let mut out = String::new();
+
+        if let Some(caption) = &self.caption {
+            let _ = writeln!(out, "{ANSI_BOLD}{caption}{ANSI_RESET}");
+        }
+
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                match self.highlights.get(&(x, y)) {
+                    Some(colour) => {
+                        let _ = write!(out, "{ANSI_BOLD}{}{c}{ANSI_RESET}", colour.ansi());
+                    }
+                    None => out.push(*c),
+                }
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Renders the frame as plain text.
+    /// If cells are highlighted, a mask follows the grid where each highlighted cell holds the first letter of its colour.
+    #[must_use]
+    pub fn render_ascii(&self) -> String {
+        let mut out = String::new();
+
+        if let Some(caption) = &self.caption {
+            let _ = writeln!(out, "{caption}");
+        }
+
+        for row in &self.cells {
+            out.extend(row.iter());
+            out.push('\n');
+        }
+
+        if !self.highlights.is_empty() {
+            out.push('\n');
+            for (y, row) in self.cells.iter().enumerate() {
+                out.extend((0..row.len()).map(|x| {
+                    self.highlights
+                        .get(&(x, y))
+                        .map_or('.', |colour| colour.letter())
+                }));
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+
+    /// Renders the frame as a standalone SVG image using a monospace font.
+    #[must_use]
+    pub fn render_svg(&self) -> String {
+        const CELL_WIDTH: usize = 10;
+        const CELL_HEIGHT: usize = 16;
+
+        let caption_height = if self.caption.is_some() {
+            CELL_HEIGHT
+        } else {
+            0
+        };
+        let width = self.width() * CELL_WIDTH;
+        let height = self.height() * CELL_HEIGHT + caption_height;
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="monospace" font-size="14">"#
+        );
+        let _ = writeln!(
+            out,
+            r##"<rect width="100%" height="100%" fill="#282c34"/>"##
+        );
+
+        if let Some(caption) = &self.caption {
+            let _ = writeln!(
+                out,
+                r##"<text x="0" y="{}" fill="#ffffff" font-weight="bold">{}</text>"##,
+                CELL_HEIGHT - 4,
+                escape_xml(caption)
+            );
+        }
+
+        for (y, row) in self.cells.iter().enumerate() {
+            let top = y * CELL_HEIGHT + caption_height;
+            for (x, c) in row.iter().enumerate() {
+                let left = x * CELL_WIDTH;
+                let fill = match self.highlights.get(&(x, y)) {
+                    Some(colour) => {
+                        let _ = writeln!(
+                            out,
+                            r#"<rect x="{left}" y="{top}" width="{CELL_WIDTH}" height="{CELL_HEIGHT}" fill="{}"/>"#,
+                            colour.svg()
+                        );
+                        "#282c34"
+                    }
+                    None => "#abb2bf",
+                };
+
+                if !c.is_whitespace() {
+                    let _ = writeln!(
+                        out,
+                        r#"<text x="{}" y="{}" fill="{fill}" text-anchor="middle">{}</text>"#,
+                        left + CELL_WIDTH / 2,
+                        top + CELL_HEIGHT - 4,
+                        escape_xml(&c.to_string())
+                    );
+                }
+            }
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A sequence of frames that can be played back in the terminal or exported to a folder.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<Frame>,
+    delay: Duration,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Animation {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            frames: vec![],
+            delay: Duration::from_millis(100),
+        }
+    }
+
+    /// Sets the playback speed in frames per second.
+    #[must_use]
+    pub fn with_fps(mut self, fps: f64) -> Self {
+        self.delay = Duration::from_secs_f64(1.0 / fps.max(f64::EPSILON));
+        self
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    #[must_use]
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Plays the frames in the terminal, redrawing the screen for every frame.
+    pub fn play(&self) {
+        let mut stdout = stdout();
+        for frame in &self.frames {
+            print!("{ANSI_CLEAR}{}", frame.render_ansi());
+            let _ = stdout.flush();
+            thread::sleep(self.delay);
+        }
+    }
+
+    /// Writes every frame as `frame_NNNN.svg` into `folder`.
+    pub fn export_svg(&self, folder: impl AsRef<Path>) -> io::Result<()> {
+        self.export(folder.as_ref(), "svg", Frame::render_svg)
+    }
+
+    /// Writes every frame as `frame_NNNN.txt` into `folder`.
+    pub fn export_ascii(&self, folder: impl AsRef<Path>) -> io::Result<()> {
+        self.export(folder.as_ref(), "txt", Frame::render_ascii)
+    }
+
+    fn export(
+        &self,
+        folder: &Path,
+        extension: &str,
+        render: fn(&Frame) -> String,
+    ) -> io::Result<()> {
+        fs::create_dir_all(folder)?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            fs::write(
+                folder.join(format!("frame_{i:04}.{extension}")),
+                render(frame),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Colour, Frame};
+
+    #[test]
+    fn renders_ascii_with_mask() {
+        let mut frame = Frame::new("ab\ncd");
+        frame
+            .highlight((1, 0), Colour::Red)
+            .highlight((0, 1), Colour::Blue);
+        assert_eq!(frame.render_ascii(), "ab\ncd\n\n.R\nB.\n");
+    }
+
+    #[test]
+    fn renders_ansi_highlights() {
+        let mut frame = Frame::filled(2, 1, '.');
+        frame.set((0, 0), '#').highlight((0, 0), Colour::Green);
+        assert_eq!(frame.render_ansi(), "\x1b[1m\x1b[32m#\x1b[0m.\n");
+    }
+
+    #[test]
+    fn renders_svg_escaped() {
+        let mut frame = Frame::new("<&");
+        frame.caption("step 1");
+        let svg = frame.render_svg();
+        assert!(svg.contains("&lt;"));
+        assert!(svg.contains("&amp;"));
+        assert!(svg.contains("step 1"));
+    }
+}